@@ -0,0 +1,130 @@
+//! Fit the coefficients of `OutcomeModel::DEFAULT` to the games in the crate's test data.
+//!
+//! Every position of every decided Copenhagen and Brandubh game in `resources/test/games` is
+//! labelled with the eventual winner, and a logistic regression over the position's `Features` is
+//! fitted using Newton's method. The coefficients are printed in a form which can be pasted into
+//! `src/analysis/model.rs`. Rerun this whenever the features change:
+//!
+//! ```text
+//! cargo run --release --example fit_outcome_model
+//! ```
+
+use hnefatafl::analysis::model::{Features, N_FEATURES};
+use hnefatafl::board::state::MediumBasicBoardState;
+use hnefatafl::game::Game;
+use hnefatafl::play::Play;
+use hnefatafl::preset::{boards, rules};
+use hnefatafl::rules::Ruleset;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// The number of coefficients (the bias followed by one weight per feature).
+const N_COEFFS: usize = N_FEATURES + 1;
+
+/// The number of Newton iterations, which is ample for convergence.
+const ITERATIONS: usize = 25;
+
+/// Add the features of every position of every decided game in the given file to `xs`, and
+/// whether the attacker went on to win to `ys`.
+fn load_positions(
+    rules: Ruleset,
+    board: &str,
+    fname: &str,
+    xs: &mut Vec<[f64; N_COEFFS]>,
+    ys: &mut Vec<f64>
+) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "resources", "test", "games", fname]
+        .iter()
+        .collect();
+    let text = fs::read_to_string(path).expect("Test data should be readable.");
+    for line in text.lines().filter(|l| !l.starts_with('#')) {
+        let cols: Vec<&str> = line.split(',').collect();
+        let attacker_won = match cols.last() {
+            Some(&"Black") => 1.0,
+            Some(&"White") => 0.0,
+            _ => continue
+        };
+        let mut game: Game<MediumBasicBoardState> = Game::new(rules, board)
+            .expect("Preset board should be valid.");
+        for token in cols[0].split(' ') {
+            // Plays may be followed by the tiles they capture, as in "d2-e2xd3".
+            let Ok(play) = Play::from_str(token.split('x').next().unwrap_or_default()) else {
+                continue
+            };
+            let features = Features::extract(&game.logic, &game.state).to_array();
+            let mut x = [1.0; N_COEFFS];
+            x[1..].copy_from_slice(&features);
+            xs.push(x);
+            ys.push(attacker_won);
+            if game.do_play(play).is_err() {
+                break
+            }
+        }
+    }
+}
+
+/// Solve the linear system `a * x = b` by Gaussian elimination with partial pivoting.
+fn solve(mut a: [[f64; N_COEFFS]; N_COEFFS], mut b: [f64; N_COEFFS]) -> [f64; N_COEFFS] {
+    for col in 0..N_COEFFS {
+        let pivot = (col..N_COEFFS)
+            .max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))
+            .unwrap_or(col);
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        let pivot_row = a[col];
+        for row in col + 1..N_COEFFS {
+            let factor = a[row][col] / pivot_row[col];
+            for (v, p) in a[row][col..].iter_mut().zip(&pivot_row[col..]) {
+                *v -= factor * p;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+    let mut x = [0.0; N_COEFFS];
+    for row in (0..N_COEFFS).rev() {
+        let sum: f64 = (row + 1..N_COEFFS).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    x
+}
+
+/// Fit a logistic regression of `ys` on `xs` using Newton's method.
+fn fit(xs: &[[f64; N_COEFFS]], ys: &[f64]) -> [f64; N_COEFFS] {
+    let mut coeffs = [0.0; N_COEFFS];
+    for _ in 0..ITERATIONS {
+        let mut gradient = [0.0; N_COEFFS];
+        // A tiny ridge term keeps the Hessian invertible if a feature never varies.
+        let mut hessian = [[0.0; N_COEFFS]; N_COEFFS];
+        for (i, row) in hessian.iter_mut().enumerate() {
+            row[i] = 1e-9;
+        }
+        for (x, y) in xs.iter().zip(ys) {
+            let z: f64 = x.iter().zip(coeffs).map(|(x, c)| x * c).sum();
+            let p = 1.0 / (1.0 + (-z).exp());
+            for i in 0..N_COEFFS {
+                gradient[i] += (y - p) * x[i];
+                for j in 0..N_COEFFS {
+                    hessian[i][j] += p * (1.0 - p) * x[i] * x[j];
+                }
+            }
+        }
+        let step = solve(hessian, gradient);
+        for (c, s) in coeffs.iter_mut().zip(step) {
+            *c += s;
+        }
+    }
+    coeffs
+}
+
+fn main() {
+    let mut xs = vec![];
+    let mut ys = vec![];
+    load_positions(rules::COPENHAGEN, boards::COPENHAGEN, "copenhagen.csv", &mut xs, &mut ys);
+    load_positions(rules::BRANDUBH, boards::BRANDUBH, "brandubh.csv", &mut xs, &mut ys);
+    println!("Fitted to {} positions.", xs.len());
+    let coeffs = fit(&xs, &ys);
+    let weights: Vec<String> = coeffs[1..].iter().map(|w| format!("{w:.4}")).collect();
+    println!("bias: {:.4},", coeffs[0]);
+    println!("weights: [{}]", weights.join(", "));
+}
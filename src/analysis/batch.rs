@@ -0,0 +1,139 @@
+use crate::analysis::playout::{random_playout, PlayoutResult};
+use crate::board::state::BoardState;
+use crate::game::logic::GameLogic;
+use crate::game::state::GameState;
+use crate::game::Game;
+use crate::game::GameStatus::Ongoing;
+use crate::utils::SplitMix64;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Count the number of distinct sequences of `depth` plays that can be made from the given
+/// position ("perft"). Sequences that end the game early are not counted. This is mainly useful
+/// for testing and benchmarking move generation.
+pub fn perft<T: BoardState>(logic: &GameLogic, state: &GameState<T>, depth: usize) -> u64 {
+    if depth == 0 {
+        return 1
+    }
+    if state.status != Ongoing {
+        return 0
+    }
+    logic.valid_plays(state).into_iter()
+        .map(|p| perft(logic, &logic.do_valid_play(p, *state).new_state, depth - 1))
+        .sum()
+}
+
+/// Run [`perft`] to the given depth for each of the given positions.
+pub fn bulk_perft<T: BoardState>(logic: &GameLogic, states: &[GameState<T>], depth: usize) -> Vec<u64> {
+    states.iter().map(|s| perft(logic, s, depth)).collect()
+}
+
+/// The generator used for the playout with the given index in a batch of playouts, so that each
+/// playout is reproducible regardless of the order in which the batch is run.
+fn playout_rng(seed: u64, index: usize) -> SplitMix64 {
+    SplitMix64::new(seed.wrapping_add(index as u64))
+}
+
+/// Run `n` random playouts (see [`random_playout`]) from the given position. The playout with
+/// index `i` uses a [`SplitMix64`] generator seeded with `seed + i`, so results are reproducible
+/// and identical to those of `par_bulk_playouts`.
+pub fn bulk_playouts<T: BoardState>(
+    logic: &GameLogic,
+    state: GameState<T>,
+    n: usize,
+    max_plays: usize,
+    seed: u64
+) -> Vec<PlayoutResult> {
+    (0..n).map(|i| random_playout(logic, state, max_plays, &mut playout_rng(seed, i))).collect()
+}
+
+/// Apply the given analysis function to each of a collection of games, such as an archive of
+/// past games, returning the results in the same order as the games.
+pub fn analyse_games<T: BoardState, R>(games: &[Game<T>], f: impl Fn(&Game<T>) -> R) -> Vec<R> {
+    games.iter().map(f).collect()
+}
+
+/// Parallel version of [`bulk_perft`].
+#[cfg(feature = "parallel")]
+pub fn par_bulk_perft<T: BoardState + Send + Sync>(
+    logic: &GameLogic,
+    states: &[GameState<T>],
+    depth: usize
+) -> Vec<u64> {
+    states.par_iter().map(|s| perft(logic, s, depth)).collect()
+}
+
+/// Parallel version of [`bulk_playouts`]. Produces the same results as the sequential version.
+#[cfg(feature = "parallel")]
+pub fn par_bulk_playouts<T: BoardState + Send + Sync>(
+    logic: &GameLogic,
+    state: GameState<T>,
+    n: usize,
+    max_plays: usize,
+    seed: u64
+) -> Vec<PlayoutResult> {
+    (0..n).into_par_iter()
+        .map(|i| random_playout(logic, state, max_plays, &mut playout_rng(seed, i)))
+        .collect()
+}
+
+/// Parallel version of [`analyse_games`].
+#[cfg(feature = "parallel")]
+pub fn par_analyse_games<T: BoardState + Send + Sync, R: Send>(
+    games: &[Game<T>],
+    f: impl Fn(&Game<T>) -> R + Sync + Send
+) -> Vec<R> {
+    games.par_iter().map(f).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::batch::{analyse_games, bulk_perft, bulk_playouts, perft};
+    use crate::game::logic::GameLogic;
+    use crate::game::state::SmallBasicGameState;
+    use crate::game::SmallBasicGame;
+    use crate::preset::{boards, rules};
+
+    #[test]
+    fn test_batch() {
+        let logic = GameLogic::new(rules::BRANDUBH, 7);
+        let state = SmallBasicGameState::new(boards::BRANDUBH, rules::BRANDUBH.starting_side).unwrap();
+        assert_eq!(perft(&logic, &state, 0), 1);
+        let n1 = perft(&logic, &state, 1);
+        assert_eq!(n1, logic.valid_plays(&state).len() as u64);
+        let n2: u64 = logic.valid_plays(&state).into_iter()
+            .map(|p| logic.valid_plays(&logic.do_valid_play(p, state).new_state).len() as u64)
+            .sum();
+        assert_eq!(perft(&logic, &state, 2), n2);
+        assert_eq!(bulk_perft(&logic, &[state, state], 1), vec![n1, n1]);
+
+        let playouts = bulk_playouts(&logic, state, 5, 200, 7);
+        assert_eq!(playouts.len(), 5);
+        assert_eq!(playouts, bulk_playouts(&logic, state, 5, 200, 7));
+        assert_eq!(playouts[1..], bulk_playouts(&logic, state, 4, 200, 8)[..]);
+
+        let games = vec![SmallBasicGame::new(rules::BRANDUBH, boards::BRANDUBH).unwrap(); 2];
+        assert_eq!(analyse_games(&games, |g| g.play_history.len()), vec![0, 0]);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_batch() {
+        use crate::analysis::batch::{par_analyse_games, par_bulk_perft, par_bulk_playouts};
+        use crate::play::Play;
+        use std::str::FromStr;
+
+        let logic = GameLogic::new(rules::BRANDUBH, 7);
+        let state = SmallBasicGameState::new(boards::BRANDUBH, rules::BRANDUBH.starting_side).unwrap();
+        let states: Vec<SmallBasicGameState> = logic.valid_plays(&state).into_iter()
+            .map(|p| logic.do_valid_play(p, state).new_state)
+            .collect();
+        assert_eq!(par_bulk_perft(&logic, &states, 2), bulk_perft(&logic, &states, 2));
+        assert_eq!(par_bulk_playouts(&logic, state, 16, 200, 7), bulk_playouts(&logic, state, 16, 200, 7));
+
+        let mut games = vec![SmallBasicGame::new(rules::BRANDUBH, boards::BRANDUBH).unwrap(); 3];
+        games[1].do_play(Play::from_str("d1-c1").unwrap()).unwrap();
+        let f = |g: &SmallBasicGame| (g.play_history.len(), g.valid_plays().len());
+        assert_eq!(par_analyse_games(&games, f), analyse_games(&games, f));
+    }
+}
@@ -0,0 +1,168 @@
+use crate::board::state::BoardState;
+use crate::game::logic::GameLogic;
+use crate::game::state::GameState;
+use crate::pieces::Side::{Attacker, Defender};
+use crate::pieces::{Piece, Side};
+use crate::tiles::Tile;
+use std::collections::{HashMap, HashSet};
+
+/// Information about who controls a single key tile.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TileControl {
+    /// The tile in question.
+    pub tile: Tile,
+    /// The piece currently occupying the tile, if any.
+    pub occupant: Option<Piece>,
+    /// The number of attacking pieces that could move onto the tile with their next play.
+    pub attacker_guards: u8,
+    /// The number of defending pieces that could move onto the tile with their next play.
+    pub defender_guards: u8,
+    /// Whether the piece occupying the tile could be captured by the opponent's next play.
+    pub attackable: bool
+}
+
+impl TileControl {
+
+    /// The number of pieces of the given side that could move onto the tile with their next play.
+    pub fn guards(&self, side: Side) -> u8 {
+        match side {
+            Attacker => self.attacker_guards,
+            Defender => self.defender_guards
+        }
+    }
+
+    /// The side that controls the tile, if any. An occupied tile is controlled by the side of the
+    /// occupying piece unless that piece can be captured. An empty tile is controlled by a side if
+    /// only that side can move a piece onto it.
+    pub fn controller(&self) -> Option<Side> {
+        if let Some(piece) = self.occupant {
+            if self.attackable { None } else { Some(piece.side) }
+        } else {
+            match (self.attacker_guards > 0, self.defender_guards > 0) {
+                (true, false) => Some(Attacker),
+                (false, true) => Some(Defender),
+                _ => None
+            }
+        }
+    }
+}
+
+/// Control of the key tiles of the board, ie, the tiles from which the corners can be approached
+/// and the tiles beside the throne.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySquares {
+    /// The tiles beside each corner.
+    pub corner_approaches: Vec<TileControl>,
+    /// The tiles beside the throne.
+    pub throne_adjacent: Vec<TileControl>
+}
+
+impl KeySquares {
+
+    /// Iterate over all key tiles.
+    pub fn iter(&self) -> impl Iterator<Item=&TileControl> {
+        self.corner_approaches.iter().chain(self.throne_adjacent.iter())
+    }
+
+    /// The number of key tiles controlled by the given side.
+    pub fn controlled_by(&self, side: Side) -> usize {
+        self.iter().filter(|tc| tc.controller() == Some(side)).count()
+    }
+}
+
+/// The tiles each side could reach and the pieces it could capture with its next play.
+#[derive(Default)]
+struct Reach {
+    destinations: HashMap<Tile, u8>,
+    threatened: HashSet<Tile>
+}
+
+/// Work out every tile the given side could move a piece to, and every tile containing a piece it
+/// could capture, if it were that side's turn to play.
+fn side_reach<T: BoardState>(logic: &GameLogic, side: Side, state: &GameState<T>) -> Reach {
+    let mut reach = Reach::default();
+    let mut side_state = *state;
+    side_state.side_to_play = side;
+    for tile in state.board.iter_occupied(side) {
+        for vp in logic.iter_plays(tile, &side_state).expect("Tile must not be empty.") {
+            let play = vp.play;
+            *reach.destinations.entry(play.to()).or_default() += 1;
+            let mut after = side_state;
+            let piece = after.board.move_piece(play.from, play.to());
+            reach.threatened.extend(
+                logic.get_captures(play, piece, &after).iter().map(|c| c.tile)
+            );
+        }
+    }
+    reach
+}
+
+/// Assess which side controls each of the key tiles of the board, ie, the tiles beside the corners
+/// and the throne.
+pub fn key_square_control<T: BoardState>(logic: &GameLogic, state: &GameState<T>) -> KeySquares {
+    let att_reach = side_reach(logic, Attacker, state);
+    let def_reach = side_reach(logic, Defender, state);
+    let tile_control = |tile: Tile| {
+        let occupant = state.board.get_piece(tile);
+        let attackable = match occupant {
+            Some(p) => match p.side {
+                Attacker => def_reach.threatened.contains(&tile),
+                Defender => att_reach.threatened.contains(&tile)
+            },
+            None => false
+        };
+        TileControl {
+            tile,
+            occupant,
+            attacker_guards: att_reach.destinations.get(&tile).copied().unwrap_or_default(),
+            defender_guards: def_reach.destinations.get(&tile).copied().unwrap_or_default(),
+            attackable
+        }
+    };
+    let geo = &logic.board_geo;
+    KeySquares {
+        corner_approaches: geo.special_tiles.corners.iter()
+            .flat_map(|c| geo.neighbors(*c))
+            .map(tile_control)
+            .collect(),
+        throne_adjacent: geo.neighbors(geo.special_tiles.throne).into_iter()
+            .map(tile_control)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::control::key_square_control;
+    use crate::game::logic::GameLogic;
+    use crate::game::state::SmallBasicGameState;
+    use crate::pieces::Side::{Attacker, Defender};
+    use crate::preset::{boards, rules};
+    use crate::tiles::Tile;
+
+    #[test]
+    fn test_key_square_control() {
+        let logic = GameLogic::new(rules::BRANDUBH, 7);
+        let state = SmallBasicGameState::new(boards::BRANDUBH, Attacker).unwrap();
+        let ks = key_square_control(&logic, &state);
+        assert_eq!(ks.corner_approaches.len(), 8);
+        assert_eq!(ks.throne_adjacent.len(), 4);
+        // Each tile beside the throne is occupied by a defender which cannot be captured.
+        assert!(ks.throne_adjacent.iter().all(|tc| tc.controller() == Some(Defender)));
+        // The corner approaches can be reached by two attackers (one along the edge and one from
+        // the middle row or column) but no defenders.
+        let a1 = ks.corner_approaches.iter().find(|tc| tc.tile == Tile::new(0, 1)).unwrap();
+        assert_eq!(a1.attacker_guards, 2);
+        assert_eq!(a1.defender_guards, 0);
+        assert_eq!(a1.controller(), Some(Attacker));
+
+        // Defender on (1, 0) can be captured by the attacker moving to (2, 0).
+        let state = SmallBasicGameState::new("7/T6/5t1/3K3/7/7/7", Attacker).unwrap();
+        let ks = key_square_control(&logic, &state);
+        let tc = ks.corner_approaches.iter().find(|tc| tc.tile == Tile::new(1, 0)).unwrap();
+        assert!(tc.attackable);
+        assert_eq!(tc.controller(), None);
+        assert_eq!(ks.controlled_by(Attacker), 2);
+        assert_eq!(ks.controlled_by(Defender), 5);
+    }
+}
@@ -0,0 +1,234 @@
+use crate::analysis::model::OutcomeModel;
+use crate::board::state::BoardState;
+use crate::engine::Engine;
+use crate::game::logic::GameLogic;
+use crate::game::state::GameState;
+use crate::game::Game;
+use crate::game::GameStatus::Ongoing;
+use crate::pieces::Side;
+use crate::pieces::Side::{Attacker, Defender};
+use crate::play::Play;
+
+/// Something which can assign a numerical evaluation to a position, from the attacker's
+/// perspective (higher values favour the attacker).
+pub trait Evaluator<T: BoardState> {
+
+    /// Evaluate the given position.
+    fn evaluate(&mut self, logic: &GameLogic, state: &GameState<T>) -> f64;
+}
+
+/// Evaluates positions as the attacker's probability of winning.
+impl<T: BoardState> Evaluator<T> for OutcomeModel {
+    fn evaluate(&mut self, logic: &GameLogic, state: &GameState<T>) -> f64 {
+        self.win_probability(logic, state).attacker
+    }
+}
+
+/// The evaluation of a single play in an [`EvalTable`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct EvalRow {
+    /// The number of the play in the game, counting from 1.
+    pub number: usize,
+    /// The side which made the play.
+    pub side: Side,
+    /// The play made.
+    pub play: Play,
+    /// The evaluation of the position after the play, if available.
+    pub eval: Option<f64>,
+    /// The best play available to the side instead, if any.
+    pub best_play: Option<Play>,
+    /// The evaluation of the position after the best play, if available.
+    pub best_eval: Option<f64>
+}
+
+/// A table evaluating each play of a game, for plotting evaluation graphs or finding mistakes in
+/// external tools. Evaluations are from the attacker's perspective.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EvalTable {
+    /// One row per play, in order.
+    pub rows: Vec<EvalRow>
+}
+
+fn side_str(side: Side) -> &'static str {
+    match side {
+        Attacker => "attacker",
+        Defender => "defender"
+    }
+}
+
+impl EvalTable {
+
+    /// Evaluate each play of the given game using the given evaluator. The best alternative to
+    /// each play is found by evaluating the position after every valid play, and choosing the
+    /// one most favourable to the side making the play.
+    pub fn from_evaluator<T: BoardState>(game: &Game<T>, evaluator: &mut dyn Evaluator<T>) -> Self {
+        let logic = &game.logic;
+        let mut state = game.state_at(0).expect("Starting state should always be available.");
+        let mut rows = vec![];
+        for (i, record) in game.play_history.iter().enumerate() {
+            let best = logic.valid_plays(&state).into_iter()
+                .map(|vp| {
+                    let after = logic.do_valid_play(vp, state).new_state;
+                    (vp.play, evaluator.evaluate(logic, &after))
+                })
+                .reduce(|best, candidate| {
+                    let better = match record.side {
+                        Attacker => candidate.1 > best.1,
+                        Defender => candidate.1 < best.1
+                    };
+                    if better { candidate } else { best }
+                });
+            state = logic.do_play(record.play, state)
+                .expect("Plays in the game's history should be valid.")
+                .new_state;
+            rows.push(EvalRow {
+                number: i + 1,
+                side: record.side,
+                play: record.play,
+                eval: Some(evaluator.evaluate(logic, &state)),
+                best_play: best.map(|(p, _)| p),
+                best_eval: best.map(|(_, e)| e)
+            });
+        }
+        Self { rows }
+    }
+
+    /// Evaluate each play of the given game using the given engine, by asking it to choose a play
+    /// in each position of the game. The best alternative to each play is the play the engine
+    /// chose instead. Evaluations are the engine's evaluation of the position after the play (or
+    /// the best alternative), so are unavailable if that position ends the game.
+    pub fn from_engine<T: BoardState>(game: &Game<T>, engine: &mut dyn Engine<T>) -> Self {
+        let mut ask = |g: &Game<T>| (g.state.status == Ongoing).then(|| engine.choose_play(g));
+        // Work backwards through the game, undoing one play at a time.
+        let mut game = game.clone();
+        let mut rows = vec![];
+        let mut after = ask(&game);
+        while let Some(record) = game.play_history.last() {
+            let (number, side, play) = (game.play_history.len(), record.side, record.play);
+            // Also undo any swap of sides following the play.
+            while game.play_history.len() == number {
+                game.undo_last_play();
+            }
+            let before = ask(&game);
+            let best_eval = match before {
+                Some(m) if m.play == play => after.and_then(|a| a.eval),
+                Some(m) => {
+                    let mut alternative = game.clone();
+                    alternative.do_play(m.play).expect("Engine should choose a valid play.");
+                    ask(&alternative).and_then(|a| a.eval)
+                },
+                None => None
+            };
+            rows.push(EvalRow {
+                number,
+                side,
+                play,
+                eval: after.and_then(|a| a.eval),
+                best_play: before.map(|m| m.play),
+                best_eval
+            });
+            after = before;
+        }
+        rows.reverse();
+        Self { rows }
+    }
+
+    /// The table as CSV, with a header row. Unavailable values are left empty.
+    pub fn to_csv(&self) -> String {
+        let opt = |v: Option<String>| v.unwrap_or_default();
+        let mut lines = vec![String::from("number,side,play,eval,best_play,best_eval")];
+        for row in &self.rows {
+            lines.push(format!(
+                "{},{},{},{},{},{}",
+                row.number,
+                side_str(row.side),
+                row.play,
+                opt(row.eval.map(|e| e.to_string())),
+                opt(row.best_play.map(|p| p.to_string())),
+                opt(row.best_eval.map(|e| e.to_string()))
+            ));
+        }
+        lines.join("\n")
+    }
+
+    /// The table as a JSON array, with one object per row. Unavailable (or non-finite) values are
+    /// `null`.
+    pub fn to_json(&self) -> String {
+        let num = |v: Option<f64>| match v {
+            Some(e) if e.is_finite() => e.to_string(),
+            _ => String::from("null")
+        };
+        let rows: Vec<String> = self.rows.iter().map(|row| format!(
+            "{{\"number\":{},\"side\":\"{}\",\"play\":\"{}\",\"eval\":{},\"best_play\":{},\"best_eval\":{}}}",
+            row.number,
+            side_str(row.side),
+            row.play,
+            num(row.eval),
+            row.best_play.map_or(String::from("null"), |p| format!("\"{p}\"")),
+            num(row.best_eval)
+        )).collect();
+        format!("[{}]", rows.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::evals::{EvalTable, Evaluator};
+    use crate::analysis::model::OutcomeModel;
+    use crate::board::state::{BoardState, SmallBasicBoardState};
+    use crate::engine::test_utils::FirstPlay;
+    use crate::game::logic::GameLogic;
+    use crate::game::state::GameState;
+    use crate::game::SmallBasicGame;
+    use crate::pieces::Side::{Attacker, Defender};
+    use crate::play::Play;
+    use crate::preset::rules;
+    use std::str::FromStr;
+
+    /// Evaluates a position by the number of attackers on the board.
+    struct CountAttackers;
+
+    impl Evaluator<SmallBasicBoardState> for CountAttackers {
+        fn evaluate(&mut self, _logic: &GameLogic, state: &GameState<SmallBasicBoardState>) -> f64 {
+            state.board.count_pieces(Attacker) as f64
+        }
+    }
+
+    #[test]
+    fn test_eval_table() {
+        let mut game = SmallBasicGame::new(rules::BRANDUBH, "7/3T3/1t5/1T5/5t1/4K2/7").unwrap();
+        game.do_play(Play::from_str("f5-f4").unwrap()).unwrap();
+        game.do_play(Play::from_str("e6-e5").unwrap()).unwrap();
+
+        let table = EvalTable::from_evaluator(&game, &mut CountAttackers);
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0].side, Attacker);
+        assert_eq!(table.rows[0].eval, Some(2.0));
+        assert_eq!(table.rows[0].best_eval, Some(2.0));
+        // The defender could have captured the attacker on b3 by moving to b2.
+        assert_eq!(table.rows[1].side, Defender);
+        assert_eq!(table.rows[1].eval, Some(2.0));
+        assert_eq!(table.rows[1].best_eval, Some(1.0));
+        assert_eq!(table.rows[1].best_play, Some(Play::from_str("d2-b2").unwrap()));
+
+        // The engine evaluates each position by the number of plays made, so the evaluation after
+        // the best play is the same as after the play actually made.
+        let table = EvalTable::from_engine(&game, &mut FirstPlay);
+        assert_eq!(table.rows[0].eval, Some(1.0));
+        assert_eq!(table.rows[0].best_eval, Some(1.0));
+        assert_eq!(table.rows[1].eval, Some(2.0));
+        assert_eq!(table.rows[1].best_eval, Some(2.0));
+        assert_eq!(table.rows[0].best_play, Some(game.logic.valid_plays(&game.state_at(0).unwrap())[0].play));
+
+        let csv = table.to_csv();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("number,side,play,eval,best_play,best_eval"));
+        assert!(lines.next().unwrap().starts_with("1,attacker,f5-f4,1,"));
+        let json = table.to_json();
+        assert!(json.starts_with("[{\"number\":1,\"side\":\"attacker\",\"play\":\"f5-f4\",\"eval\":1,"));
+        assert!(json.ends_with("\"best_eval\":2}]"));
+
+        let table = EvalTable::from_evaluator(&game, &mut OutcomeModel::default());
+        assert!(table.rows.iter().all(|r| r.eval.is_some_and(|e| (0.0..=1.0).contains(&e))));
+    }
+}
@@ -0,0 +1,6 @@
+pub mod batch;
+pub mod control;
+pub mod evals;
+pub mod model;
+pub mod playout;
+pub mod stats;
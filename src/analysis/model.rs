@@ -0,0 +1,335 @@
+use crate::analysis::control::key_square_control;
+use crate::board::state::BoardState;
+use crate::game::logic::GameLogic;
+use crate::game::state::GameState;
+use crate::game::GameOutcome::{Draw, Win};
+use crate::game::GameStatus::{Ongoing, Over};
+use crate::pieces::Side::{Attacker, Defender};
+use crate::pieces::{Side, KING};
+use crate::play::Play;
+use crate::tiles::AxisOffset;
+use crate::tiles::Axis::{Horizontal, Vertical};
+
+/// The number of features used by [`OutcomeModel`].
+pub const N_FEATURES: usize = 7;
+
+/// The scale of the fixed-point values used by [`Features::extract_fixed`] and
+/// [`OutcomeModel::score`]: a value of `SCORE_SCALE` represents `1.0`.
+pub const SCORE_SCALE: i64 = 1000;
+
+/// Numeric features extracted from a position, used as the inputs to an [`OutcomeModel`]. Each
+/// feature is scaled so that it is roughly in the range `-1.0..=1.0`, regardless of board size.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Features {
+    /// Material balance (see [`GameLogic::material_balance`]) as a proportion of all material on
+    /// the board. Positive values favour the attacker.
+    pub material: f64,
+    /// The proportion of the key tiles (see [`key_square_control`]) controlled by the attacker,
+    /// less the proportion controlled by the defender.
+    pub key_squares: f64,
+    /// The number of plays available to the king, relative to the maximum possible.
+    pub king_mobility: f64,
+    /// The number of directions in which the king has a clear line to the edge of the board.
+    pub king_open_lines: f64,
+    /// The king's distance from the nearest tile it must reach to win (the target tile, corner or
+    /// edge, depending on the rules), relative to the board size.
+    pub king_distance: f64,
+    /// The number of hostile pieces or tiles beside the king.
+    pub king_pressure: f64,
+    /// `1.0` if it is the attacker's turn to play and `-1.0` otherwise.
+    pub attacker_to_play: f64
+}
+
+impl Features {
+
+    /// Extract the features of the given position.
+    pub fn extract<T: BoardState>(logic: &GameLogic, state: &GameState<T>) -> Self {
+        let [
+            material,
+            key_squares,
+            king_mobility,
+            king_open_lines,
+            king_distance,
+            king_pressure,
+            attacker_to_play
+        ] = Self::ratios(logic, state).map(|(n, d)| if d == 0 { 0.0 } else { n as f64 / d as f64 });
+        Self {
+            material,
+            key_squares,
+            king_mobility,
+            king_open_lines,
+            king_distance,
+            king_pressure,
+            attacker_to_play
+        }
+    }
+
+    /// Extract the features of the given position in fixed point, as multiples of
+    /// `1 / SCORE_SCALE`, in the order returned by [`Self::to_array`]. Unlike [`Self::extract`],
+    /// this uses only integer arithmetic, so gives identical results on every platform.
+    pub fn extract_fixed<T: BoardState>(
+        logic: &GameLogic,
+        state: &GameState<T>
+    ) -> [i64; N_FEATURES] {
+        Self::ratios(logic, state).map(|(n, d)| if d == 0 { 0 } else { n * SCORE_SCALE / d })
+    }
+
+    /// Each feature as a numerator and denominator, in the order returned by [`Self::to_array`].
+    fn ratios<T: BoardState>(logic: &GameLogic, state: &GameState<T>) -> [(i64, i64); N_FEATURES] {
+        let board = &state.board;
+        let geo = &logic.board_geo;
+        let side_len = geo.side_len as i64;
+        let king = board.get_king();
+
+        let att_material = logic.material(Attacker, board) as i64;
+        let def_material = logic.material(Defender, board) as i64;
+        let material = (att_material - def_material, att_material + def_material);
+
+        let key_squares = key_square_control(logic, state);
+        let key_squares = (
+            key_squares.controlled_by(Attacker) as i64 - key_squares.controlled_by(Defender) as i64,
+            key_squares.iter().count() as i64
+        );
+
+        let mut king_state = *state;
+        king_state.side_to_play = Defender;
+        let king_mobility = (
+            logic.iter_plays(king, &king_state).map(|i| i.count()).unwrap_or_default() as i64,
+            2 * (side_len - 1)
+        );
+
+        let mut open_lines = 0;
+        for axis in [Vertical, Horizontal] {
+            for dir in [-1i8, 1] {
+                let mut d = dir;
+                loop {
+                    let coords = Play::new(king, AxisOffset::new(axis, d)).to_coords();
+                    match geo.coords_to_tile(coords) {
+                        Ok(t) if board.tile_occupied(t) => break,
+                        Ok(_) => d += dir,
+                        Err(_) => {
+                            open_lines += 1;
+                            break
+                        }
+                    }
+                }
+            }
+        }
+
+        let last = geo.side_len - 1;
+        let king_distance = if let Some(target) = logic.rules.king_target {
+            let target = logic.king_target_tile(target);
+            king.row.abs_diff(target.row) + king.col.abs_diff(target.col)
+        } else if logic.rules.edge_escape {
+            king.row.min(last - king.row).min(king.col).min(last - king.col)
+        } else {
+            king.row.min(last - king.row) + king.col.min(last - king.col)
+        };
+
+        let king_pressure = geo.neighbors(king).iter()
+            .filter(|t| logic.tile_hostile(**t, KING, board))
+            .count();
+
+        [
+            material,
+            key_squares,
+            king_mobility,
+            (open_lines, 4),
+            (king_distance as i64, side_len),
+            (king_pressure as i64, 4),
+            (if state.side_to_play == Attacker { 1 } else { -1 }, 1)
+        ]
+    }
+
+    /// The features as an array, in the order in which they are declared.
+    pub fn to_array(&self) -> [f64; N_FEATURES] {
+        [
+            self.material,
+            self.key_squares,
+            self.king_mobility,
+            self.king_open_lines,
+            self.king_distance,
+            self.king_pressure,
+            self.attacker_to_play
+        ]
+    }
+}
+
+/// An estimate of each side's chance of winning from a given position.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct WinProbability {
+    /// The probability that the attacker wins.
+    pub attacker: f64,
+    /// The probability that the defender wins.
+    pub defender: f64
+}
+
+impl WinProbability {
+
+    /// The probability that the given side wins.
+    pub fn for_side(&self, side: Side) -> f64 {
+        match side {
+            Attacker => self.attacker,
+            Defender => self.defender
+        }
+    }
+}
+
+/// A simple logistic regression model mapping the [`Features`] of a position to the probability
+/// of each side winning. This is intended to be cheap enough to drive an "evaluation bar" in a
+/// user interface, not to be a substitute for a proper engine.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OutcomeModel {
+    /// The intercept term.
+    pub bias: f64,
+    /// The weight of each feature, in the order returned by [`Features::to_array`].
+    pub weights: [f64; N_FEATURES]
+}
+
+impl OutcomeModel {
+
+    /// Coefficients fitted offline to every position of the decided Copenhagen and Brandubh games
+    /// in the crate's test data, labelled with the eventual winner. They can be refitted (for
+    /// example, after changing the features) by running the `fit_outcome_model` example.
+    pub const DEFAULT: Self = Self {
+        bias: -2.1474,
+        weights: [5.5229, 2.6798, -0.0530, -0.9452, 1.1309, 0.5044, 0.0909]
+    };
+
+    /// The estimated probability of the attacker winning, given the features of a position.
+    pub fn predict(&self, features: &Features) -> f64 {
+        let z = features.to_array().iter()
+            .zip(self.weights.iter())
+            .fold(self.bias, |acc, (x, w)| acc + x * w);
+        1.0 / (1.0 + (-z).exp())
+    }
+
+    /// The model's weights (and bias, which is the first element) in fixed point, as multiples of
+    /// `1 / SCORE_SCALE`.
+    fn fixed_weights(&self) -> [i64; N_FEATURES + 1] {
+        let mut weights = [(self.bias * SCORE_SCALE as f64).round() as i64; N_FEATURES + 1];
+        for (fixed, w) in weights[1..].iter_mut().zip(self.weights) {
+            *fixed = (w * SCORE_SCALE as f64).round() as i64;
+        }
+        weights
+    }
+
+    /// An integer score for the given position, from the attacker's perspective: the model's
+    /// log-odds of the attacker winning, as a multiple of `1 / SCORE_SCALE`. If the game is already
+    /// over, the score is `i32::MAX` if the attacker has won, `-i32::MAX` if the defender has won
+    /// and `0` for a draw.
+    ///
+    /// The score is ordered in the same way as [`Self::win_probability`] (up to rounding), but is
+    /// computed using only integer arithmetic, so it is identical on every platform and can be
+    /// used where results must match exactly.
+    pub fn score<T: BoardState>(&self, logic: &GameLogic, state: &GameState<T>) -> i32 {
+        match state.status {
+            Over(Win(_, Attacker)) => i32::MAX,
+            Over(Win(_, Defender)) => -i32::MAX,
+            Over(Draw(_)) => 0,
+            Ongoing => {
+                let [bias, weights @ ..] = self.fixed_weights();
+                let z = Features::extract_fixed(logic, state).iter()
+                    .zip(weights)
+                    .fold(bias * SCORE_SCALE, |acc, (x, w)| acc + x * w) / SCORE_SCALE;
+                z.clamp(-(i32::MAX as i64) + 1, i32::MAX as i64 - 1) as i32
+            }
+        }
+    }
+
+    /// Estimate each side's chance of winning from the given position. If the game is already
+    /// over, the winner is given a probability of `1.0` (or each side `0.5` in case of a draw).
+    pub fn win_probability<T: BoardState>(
+        &self,
+        logic: &GameLogic,
+        state: &GameState<T>
+    ) -> WinProbability {
+        let attacker = match state.status {
+            Over(Win(_, Attacker)) => 1.0,
+            Over(Win(_, Defender)) => 0.0,
+            Over(Draw(_)) => 0.5,
+            Ongoing => self.predict(&Features::extract(logic, state))
+        };
+        WinProbability { attacker, defender: 1.0 - attacker }
+    }
+}
+
+impl Default for OutcomeModel {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::model::{Features, OutcomeModel, SCORE_SCALE};
+    use crate::game::GameOutcome::Win;
+    use crate::game::GameStatus::Over;
+    use crate::game::WinReason::KingEscaped;
+    use crate::game::logic::GameLogic;
+    use crate::game::state::{MediumBasicGameState, SmallBasicGameState};
+    use crate::pieces::Side::{Attacker, Defender};
+    use crate::preset::{boards, rules};
+    use crate::rules::{KingTarget, Ruleset};
+    use crate::tiles::Tile;
+
+    #[test]
+    fn test_win_probability() {
+        let model = OutcomeModel::default();
+        let logic = GameLogic::new(rules::COPENHAGEN, 11);
+        let state = MediumBasicGameState::new(boards::COPENHAGEN, Attacker).unwrap();
+        let features = Features::extract(&logic, &state);
+        assert_eq!(features.material, 0.0);
+        assert_eq!(features.king_mobility, 0.0);
+        let p = model.win_probability(&logic, &state);
+        assert!((p.attacker + p.defender - 1.0).abs() < 1e-9);
+        assert!(p.attacker > 0.25 && p.attacker < 0.75);
+
+        // King with open lines to the edges and attackers down on material.
+        let logic = GameLogic::new(rules::BRANDUBH, 7);
+        let free_king = SmallBasicGameState::new("7/7/1K5/7/3T3/5t1/7", Defender).unwrap();
+        let trapped_king = SmallBasicGameState::new("7/1t5/tKt4/1t5/7/7/3T3", Defender).unwrap();
+        assert!(model.win_probability(&logic, &free_king).for_side(Defender)
+            > model.win_probability(&logic, &trapped_king).for_side(Defender));
+    }
+
+    #[test]
+    fn test_king_distance() {
+        let state = SmallBasicGameState::new("7/7/7/K6/7/7/3t3", Defender).unwrap();
+        let logic = GameLogic::new(rules::BRANDUBH, 7);
+        assert_eq!(Features::extract(&logic, &state).king_distance, 3.0 / 7.0);
+        // With a target, the distance is to the target rather than the nearest corner.
+        let target = Ruleset { king_target: Some(KingTarget::Tile(Tile::new(3, 2))), ..rules::BRANDUBH };
+        let logic = GameLogic::new(target, 7);
+        assert_eq!(Features::extract(&logic, &state).king_distance, 2.0 / 7.0);
+        // A king on a corner is far from a target on the throne.
+        let state = SmallBasicGameState::new("K6/7/7/7/7/7/3t3", Defender).unwrap();
+        let throne = Ruleset { king_target: Some(KingTarget::Throne), ..rules::BRANDUBH };
+        let logic = GameLogic::new(throne, 7);
+        assert_eq!(Features::extract(&logic, &state).king_distance, 6.0 / 7.0);
+    }
+
+    #[test]
+    fn test_score() {
+        let model = OutcomeModel::default();
+        let logic = GameLogic::new(rules::BRANDUBH, 7);
+        let free_king = SmallBasicGameState::new("7/7/1K5/7/3T3/5t1/7", Defender).unwrap();
+        let trapped_king = SmallBasicGameState::new("7/1t5/tKt4/1t5/7/7/3T3", Defender).unwrap();
+        let features = Features::extract(&logic, &free_king).to_array();
+        let fixed = Features::extract_fixed(&logic, &free_king);
+        for (f, x) in features.iter().zip(fixed) {
+            assert!(((f * SCORE_SCALE as f64) as i64).abs_diff(x) <= 1);
+        }
+        // The score is the log-odds of the attacker winning.
+        for state in [free_king, trapped_king] {
+            let p = model.win_probability(&logic, &state).attacker;
+            let logit = (p / (1.0 - p)).ln() * SCORE_SCALE as f64;
+            assert!((model.score(&logic, &state) as f64 - logit).abs() < 10.0);
+        }
+        assert!(model.score(&logic, &free_king) < model.score(&logic, &trapped_king));
+
+        let mut state = free_king;
+        state.status = Over(Win(KingEscaped, Defender));
+        assert_eq!(model.score(&logic, &state), -i32::MAX);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::board::state::BoardState;
+use crate::game::logic::GameLogic;
+use crate::game::state::GameState;
+use crate::game::movegen::PlayStage;
+use crate::game::GameOutcome;
+use crate::game::GameStatus::Over;
+use crate::play::ValidPlay;
+use crate::utils::Rng;
+
+/// The result of a single playout from some starting position.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PlayoutResult {
+    /// The outcome of the game, or `None` if the playout was stopped before the game ended.
+    pub outcome: Option<GameOutcome>,
+    /// The number of plays made during the playout.
+    pub plays: usize
+}
+
+/// A policy for choosing plays during a playout (or "rollout", as used in Monte Carlo tree
+/// search). Implement this to replace uniformly random playouts with heuristic or learned
+/// policies.
+pub trait PlayoutPolicy<T: BoardState> {
+
+    /// Choose a play for the side to play in the given (ongoing) position, or return `None` if
+    /// there are no valid plays.
+    fn choose_play(&mut self, logic: &GameLogic, state: &GameState<T>) -> Option<ValidPlay>;
+}
+
+/// A policy which chooses uniformly at random from all valid plays.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct UniformPolicy<R: Rng>(pub R);
+
+impl<T: BoardState, R: Rng> PlayoutPolicy<T> for UniformPolicy<R> {
+    fn choose_play(&mut self, logic: &GameLogic, state: &GameState<T>) -> Option<ValidPlay> {
+        self.0.choose(&logic.valid_plays(state)).copied()
+    }
+}
+
+/// A simple heuristic policy: always play a winning king move if there is one, otherwise choose
+/// at random from the plays which capture an enemy piece, if any, or from all valid plays.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CaptureFirstPolicy<R: Rng>(pub R);
+
+impl<T: BoardState, R: Rng> PlayoutPolicy<T> for CaptureFirstPolicy<R> {
+    fn choose_play(&mut self, logic: &GameLogic, state: &GameState<T>) -> Option<ValidPlay> {
+        let mut all = vec![];
+        let mut captures = vec![];
+        for staged in logic.staged_plays(state) {
+            match staged.stage {
+                PlayStage::KingEscape => return Some(staged.play),
+                PlayStage::Capture => captures.push(staged.play),
+                _ => {}
+            }
+            all.push(staged.play);
+        }
+        let candidates = if captures.is_empty() { all } else { captures };
+        self.0.choose(&candidates).copied()
+    }
+}
+
+/// Play out a game from the given position by choosing plays according to the given policy,
+/// stopping when the game ends or after `max_plays` plays.
+pub fn playout<T: BoardState>(
+    logic: &GameLogic,
+    mut state: GameState<T>,
+    max_plays: usize,
+    policy: &mut impl PlayoutPolicy<T>
+) -> PlayoutResult {
+    for plays in 0..max_plays {
+        if let Over(outcome) = state.status {
+            return PlayoutResult { outcome: Some(outcome), plays }
+        }
+        let Some(play) = policy.choose_play(logic, &state) else {
+            // Should not normally happen as having no valid plays ends the game.
+            return PlayoutResult { outcome: None, plays }
+        };
+        state = logic.do_valid_play(play, state).new_state;
+    }
+    let outcome = match state.status {
+        Over(outcome) => Some(outcome),
+        _ => None
+    };
+    PlayoutResult { outcome, plays: max_plays }
+}
+
+/// Play out a game from the given position by choosing uniformly at random from the valid plays
+/// at each turn, stopping when the game ends or after `max_plays` plays. Randomness is drawn
+/// from the given [`Rng`], so the same seeded generator always produces the same playout.
+pub fn random_playout<T: BoardState>(
+    logic: &GameLogic,
+    state: GameState<T>,
+    max_plays: usize,
+    rng: &mut impl Rng
+) -> PlayoutResult {
+    playout(logic, state, max_plays, &mut UniformPolicy(rng))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::playout::{playout, random_playout, CaptureFirstPolicy, PlayoutPolicy, UniformPolicy};
+    use crate::engine::test_utils::FirstPlay;
+    use crate::game::logic::GameLogic;
+    use crate::game::state::SmallBasicGameState;
+    use crate::pieces::Side::{Attacker, Defender};
+    use crate::play::Play;
+    use crate::preset::{boards, rules};
+    use crate::utils::SplitMix64;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_random_playout() {
+        let logic = GameLogic::new(rules::BRANDUBH, 7);
+        let state = SmallBasicGameState::new(boards::BRANDUBH, rules::BRANDUBH.starting_side).unwrap();
+        let results: Vec<_> = (0..10)
+            .map(|seed| random_playout(&logic, state, 500, &mut SplitMix64::new(seed)))
+            .collect();
+        for (seed, result) in results.iter().enumerate() {
+            assert_eq!(random_playout(&logic, state, 500, &mut SplitMix64::new(seed as u64)), *result);
+            assert!(result.plays <= 500);
+            assert!(result.outcome.is_some() || result.plays == 500);
+        }
+        assert!(results.iter().any(|r| r.outcome.is_some()));
+        assert_eq!(random_playout(&logic, state, 0, &mut SplitMix64::new(0)).plays, 0);
+    }
+
+    #[test]
+    fn test_playout_policies() {
+        let logic = GameLogic::new(rules::BRANDUBH, 7);
+        let state = SmallBasicGameState::new(boards::BRANDUBH, rules::BRANDUBH.starting_side).unwrap();
+        assert_eq!(
+            playout(&logic, state, 200, &mut UniformPolicy(SplitMix64::new(3))),
+            random_playout(&logic, state, 200, &mut SplitMix64::new(3))
+        );
+        assert_eq!(playout(&logic, state, 200, &mut FirstPlay), playout(&logic, state, 200, &mut FirstPlay));
+
+        let state = SmallBasicGameState::new("3K3/7/3t3/7/7/1tT4/7", Attacker).unwrap();
+        for seed in 0..10 {
+            assert_eq!(
+                CaptureFirstPolicy(SplitMix64::new(seed)).choose_play(&logic, &state).map(|p| p.play),
+                Some(Play::from_str("d3-d6").unwrap())
+            );
+        }
+        let state = SmallBasicGameState::new("1K5/1T5/1t5/4T2/7/7/7", Defender).unwrap();
+        let result = playout(&logic, state, 10, &mut CaptureFirstPolicy(SplitMix64::new(0)));
+        assert_eq!(result.plays, 1);
+        assert!(result.outcome.is_some());
+    }
+}
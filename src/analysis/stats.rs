@@ -0,0 +1,223 @@
+use crate::board::state::BoardState;
+use crate::error::RecordError;
+use crate::game::Game;
+use crate::game::GameOutcome::{Draw, Win};
+use crate::game::GameStatus::{Ongoing, Over};
+use crate::pieces::Side;
+use crate::pieces::Side::{Attacker, Defender};
+use crate::play::Play;
+use crate::record::GameRecord;
+use crate::rules::Ruleset;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
+
+/// Aggregate statistics about the games played under a single variant, collected by
+/// [`GameStats`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct VariantStats {
+    /// The number of games.
+    pub games: usize,
+    /// The number of games won by the attacker.
+    pub attacker_wins: usize,
+    /// The number of games won by the defender.
+    pub defender_wins: usize,
+    /// The number of games drawn.
+    pub draws: usize,
+    /// The number of games which had not finished.
+    pub unfinished: usize,
+    /// The total number of plays made across all games.
+    pub total_plays: usize,
+    /// The number of games in which the attacker captured each number of pieces.
+    pub attacker_captures: BTreeMap<u16, usize>,
+    /// The number of games in which the defender captured each number of pieces.
+    pub defender_captures: BTreeMap<u16, usize>,
+    /// The number of games beginning with each opening (the first plays of the game, up to the
+    /// opening length of the [`GameStats`]).
+    pub openings: HashMap<Vec<Play>, usize>
+}
+
+impl VariantStats {
+
+    /// The proportion of games won by the given side (including unfinished games in the total).
+    pub fn win_rate(&self, side: Side) -> f64 {
+        let wins = match side {
+            Attacker => self.attacker_wins,
+            Defender => self.defender_wins
+        };
+        self.rate(wins)
+    }
+
+    /// The proportion of games drawn (including unfinished games in the total).
+    pub fn draw_rate(&self) -> f64 {
+        self.rate(self.draws)
+    }
+
+    fn rate(&self, n: usize) -> f64 {
+        if self.games == 0 {
+            return 0.0
+        }
+        n as f64 / self.games as f64
+    }
+
+    /// The average number of plays per game.
+    pub fn average_length(&self) -> f64 {
+        self.rate(self.total_plays)
+    }
+
+    /// The average number of pieces captured by the given side per game.
+    pub fn average_captures(&self, side: Side) -> f64 {
+        let captures = match side {
+            Attacker => &self.attacker_captures,
+            Defender => &self.defender_captures
+        };
+        self.rate(captures.iter().map(|(&n, &games)| n as usize * games).sum())
+    }
+
+    /// The `n` most common openings, with the number of games beginning with each, most common
+    /// first. Openings played equally often are ordered by their plays.
+    pub fn most_common_openings(&self, n: usize) -> Vec<(Vec<Play>, usize)> {
+        let mut openings: Vec<(Vec<Play>, usize)> = self.openings.iter()
+            .map(|(plays, &count)| (plays.clone(), count))
+            .collect();
+        openings.sort_by(|(a_plays, a), (b_plays, b)| b.cmp(a).then_with(|| {
+            a_plays.iter().map(Play::to_string).cmp(b_plays.iter().map(Play::to_string))
+        }));
+        openings.truncate(n);
+        openings
+    }
+}
+
+/// Collects aggregate statistics over a collection of games, grouped by variant, to answer
+/// questions about balance such as how often each side wins.
+///
+/// Games are added one at a time, so statistics can be collected over archives of any size (for
+/// example, using [`ArchiveReader`](crate::record::ArchiveReader)). Variants are identified by
+/// name, since a [`GameRecord`] does not include its rules.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GameStats {
+    opening_len: usize,
+    variants: BTreeMap<String, VariantStats>
+}
+
+impl GameStats {
+
+    /// Create a new, empty collection of statistics, which records openings of the given number
+    /// of plays.
+    pub fn new(opening_len: usize) -> Self {
+        Self { opening_len, variants: BTreeMap::new() }
+    }
+
+    /// Add the given game, played under the named variant.
+    pub fn add_game<T: BoardState>(&mut self, variant: &str, game: &Game<T>) {
+        let stats = self.variants.entry(variant.to_string()).or_default();
+        stats.games += 1;
+        match game.state.status {
+            Over(Win(_, Attacker)) => stats.attacker_wins += 1,
+            Over(Win(_, Defender)) => stats.defender_wins += 1,
+            Over(Draw(_)) => stats.draws += 1,
+            Ongoing => stats.unfinished += 1
+        }
+        stats.total_plays += game.play_history.len();
+        *stats.attacker_captures.entry(game.state.captures_by(Attacker)).or_default() += 1;
+        *stats.defender_captures.entry(game.state.captures_by(Defender)).or_default() += 1;
+        if self.opening_len > 0 && game.play_history.len() >= self.opening_len {
+            let opening = game.play_history[..self.opening_len].iter().map(|r| r.play).collect();
+            *stats.openings.entry(opening).or_default() += 1;
+        }
+    }
+
+    /// Replay the given record under the given rules and add it as a game played under the named
+    /// variant. Returns an error (and adds nothing) if the record cannot be replayed.
+    pub fn add_record<T: BoardState>(
+        &mut self,
+        variant: &str,
+        rules: Ruleset,
+        record: &GameRecord
+    ) -> Result<(), RecordError> {
+        let game: Game<T> = record.replay(rules)?;
+        self.add_game(variant, &game);
+        Ok(())
+    }
+
+    /// The statistics for the named variant, if any of its games have been added.
+    pub fn variant(&self, name: &str) -> Option<&VariantStats> {
+        self.variants.get(name)
+    }
+
+    /// Iterate over the statistics for each variant, in order of name.
+    pub fn variants(&self) -> impl Iterator<Item=(&str, &VariantStats)> {
+        self.variants.iter().map(|(name, stats)| (name.as_str(), stats))
+    }
+}
+
+/// A short report with one line for each variant.
+impl Display for GameStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let lines: Vec<String> = self.variants().map(|(name, s)| format!(
+            "{name}: {} games, attacker {:.1}%, defender {:.1}%, drawn {:.1}%, unfinished {}, \
+            average {:.1} plays, average captures {:.1}/{:.1}",
+            s.games,
+            s.win_rate(Attacker) * 100.0,
+            s.win_rate(Defender) * 100.0,
+            s.draw_rate() * 100.0,
+            s.unfinished,
+            s.average_length(),
+            s.average_captures(Attacker),
+            s.average_captures(Defender)
+        )).collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::stats::GameStats;
+    use crate::board::state::SmallBasicBoardState;
+    use crate::game::SmallBasicGame;
+    use crate::pieces::Side::{Attacker, Defender};
+    use crate::play::Play;
+    use crate::preset::{boards, rules};
+    use crate::record::GameRecord;
+    use std::str::FromStr;
+
+    fn play_game(board: &str, plays: &[&str]) -> SmallBasicGame {
+        let mut game = SmallBasicGame::new(rules::BRANDUBH, board).unwrap();
+        for p in plays {
+            game.do_play(Play::from_str(p).unwrap()).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_game_stats() {
+        let mut stats = GameStats::new(2);
+        stats.add_game("Brandubh", &play_game("1K5/7/7/7/4t2/7/7", &["e5-e4", "b1-a1"]));
+        stats.add_game("Brandubh", &play_game("7/3T3/1t5/1T5/5t1/4K2/7", &["f5-f4", "d2-b2"]));
+        stats.add_game("Brandubh", &play_game(boards::BRANDUBH, &["d1-c1", "d3-c3", "c1-c2"]));
+        let record = GameRecord::from_game(&play_game(boards::BRANDUBH, &["d1-c1", "d3-c3"]));
+        stats.add_record::<SmallBasicBoardState>("Brandubh", rules::BRANDUBH, &record).unwrap();
+        stats.add_game("Other", &play_game(boards::BRANDUBH, &[]));
+
+        let brandubh = stats.variant("Brandubh").unwrap();
+        assert_eq!(brandubh.games, 4);
+        assert_eq!(brandubh.defender_wins, 1);
+        assert_eq!(brandubh.unfinished, 3);
+        assert_eq!(brandubh.win_rate(Defender), 0.25);
+        assert_eq!(brandubh.win_rate(Attacker), 0.0);
+        assert_eq!(brandubh.average_length(), 2.25);
+        assert_eq!(brandubh.defender_captures.get(&1), Some(&1));
+        assert_eq!(brandubh.average_captures(Defender), 0.25);
+        let openings = brandubh.most_common_openings(2);
+        assert_eq!(openings[0], (vec![Play::from_str("d1-c1").unwrap(), Play::from_str("d3-c3").unwrap()], 2));
+        assert_eq!(openings[1].0[0], Play::from_str("e5-e4").unwrap());
+
+        let other = stats.variant("Other").unwrap();
+        assert!(other.openings.is_empty());
+        assert_eq!(stats.variants().map(|(name, _)| name).collect::<Vec<_>>(), vec!["Brandubh", "Other"]);
+        assert_eq!(
+            stats.to_string().lines().next(),
+            Some("Brandubh: 4 games, attacker 0.0%, defender 25.0%, drawn 0.0%, unfinished 3, \
+                average 2.2 plays, average captures 0.0/0.2")
+        );
+    }
+}
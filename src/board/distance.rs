@@ -0,0 +1,154 @@
+use crate::tiles::Tile;
+
+/// The side length of the largest board for which distance tables are available.
+pub const MAX_BOARD_LEN: u8 = 21;
+
+const MAX_TILES: usize = MAX_BOARD_LEN as usize * MAX_BOARD_LEN as usize;
+
+/// Lookup tables giving, for each tile on a board of a particular size, the distance to the
+/// nearest corner, the nearest edge and the throne (the central tile). Distances are Manhattan
+/// distances, ie, the number of orthogonal steps between the tiles, ignoring any pieces.
+///
+/// Tables for every board size up to [`MAX_BOARD_LEN`] are computed at compile time; use
+/// [`DistanceTables::for_board`] to get the tables for a given size. Values can be looked up by
+/// [`Tile`] or, in hot loops, by tile index (`row * side_len + col`, see [`Self::index`]).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DistanceTables {
+    side_len: u8,
+    corner: [u8; MAX_TILES],
+    edge: [u8; MAX_TILES],
+    throne: [u8; MAX_TILES]
+}
+
+const fn min(a: u8, b: u8) -> u8 {
+    if a < b { a } else { b }
+}
+
+impl DistanceTables {
+
+    /// Compute the tables for a board with the given side length.
+    const fn new(side_len: u8) -> Self {
+        let mut tables = Self {
+            side_len,
+            corner: [0; MAX_TILES],
+            edge: [0; MAX_TILES],
+            throne: [0; MAX_TILES]
+        };
+        if side_len == 0 {
+            return tables
+        }
+        let last = side_len - 1;
+        let mid = side_len / 2;
+        let mut row = 0;
+        while row < side_len {
+            let mut col = 0;
+            while col < side_len {
+                let i = row as usize * side_len as usize + col as usize;
+                let row_to_edge = min(row, last - row);
+                let col_to_edge = min(col, last - col);
+                tables.corner[i] = row_to_edge + col_to_edge;
+                tables.edge[i] = min(row_to_edge, col_to_edge);
+                tables.throne[i] = row.abs_diff(mid) + col.abs_diff(mid);
+                col += 1;
+            }
+            row += 1;
+        }
+        tables
+    }
+
+    /// Get the tables for a board with the given side length. Panics if the side length is
+    /// greater than [`MAX_BOARD_LEN`].
+    pub fn for_board(side_len: u8) -> &'static Self {
+        &ALL_TABLES[side_len as usize]
+    }
+
+    /// The side length of the board to which the tables relate.
+    pub fn side_len(&self) -> u8 {
+        self.side_len
+    }
+
+    /// The index of the given tile in the tables.
+    pub fn index(&self, tile: Tile) -> usize {
+        tile.row as usize * self.side_len as usize + tile.col as usize
+    }
+
+    /// The distance from the tile with the given index to the nearest corner.
+    pub fn corner_at(&self, index: usize) -> u8 {
+        self.corner[index]
+    }
+
+    /// The distance from the tile with the given index to the nearest edge.
+    pub fn edge_at(&self, index: usize) -> u8 {
+        self.edge[index]
+    }
+
+    /// The distance from the tile with the given index to the throne.
+    pub fn throne_at(&self, index: usize) -> u8 {
+        self.throne[index]
+    }
+
+    /// The distance from the given tile to the nearest corner.
+    pub fn corner(&self, tile: Tile) -> u8 {
+        self.corner_at(self.index(tile))
+    }
+
+    /// The distance from the given tile to the nearest edge.
+    pub fn edge(&self, tile: Tile) -> u8 {
+        self.edge_at(self.index(tile))
+    }
+
+    /// The distance from the given tile to the throne.
+    pub fn throne(&self, tile: Tile) -> u8 {
+        self.throne_at(self.index(tile))
+    }
+}
+
+const fn build_all() -> [DistanceTables; MAX_BOARD_LEN as usize + 1] {
+    let mut all = [const { DistanceTables::new(0) }; MAX_BOARD_LEN as usize + 1];
+    let mut side_len = 1;
+    while side_len <= MAX_BOARD_LEN {
+        all[side_len as usize] = DistanceTables::new(side_len);
+        side_len += 1;
+    }
+    all
+}
+
+static ALL_TABLES: [DistanceTables; MAX_BOARD_LEN as usize + 1] = build_all();
+
+#[cfg(test)]
+mod tests {
+    use crate::board::distance::DistanceTables;
+    use crate::board::geometry::BoardGeometry;
+    use crate::tiles::Tile;
+
+    #[test]
+    fn test_distance_tables() {
+        let tables = DistanceTables::for_board(7);
+        assert_eq!(tables.side_len(), 7);
+        assert_eq!(tables.corner(Tile::new(0, 0)), 0);
+        assert_eq!(tables.corner(Tile::new(3, 3)), 6);
+        assert_eq!(tables.corner(Tile::new(1, 5)), 2);
+        assert_eq!(tables.edge(Tile::new(1, 5)), 1);
+        assert_eq!(tables.edge(Tile::new(3, 3)), 3);
+        assert_eq!(tables.throne(Tile::new(3, 3)), 0);
+        assert_eq!(tables.throne(Tile::new(0, 0)), 6);
+        let i = tables.index(Tile::new(1, 5));
+        assert_eq!(i, 12);
+        assert_eq!(tables.corner_at(i), 2);
+
+        for side_len in [7, 9, 11, 13, 15, 19, 21] {
+            let geo = BoardGeometry::new(side_len);
+            let tables = DistanceTables::for_board(side_len);
+            for t in geo.iter_tiles() {
+                let corner = geo.special_tiles.corners.iter()
+                    .map(|c| t.row.abs_diff(c.row) + t.col.abs_diff(c.col))
+                    .min()
+                    .unwrap();
+                assert_eq!(tables.corner(t), corner);
+                assert_eq!(tables.edge(t) == 0, geo.tile_at_edge(t));
+                let throne = geo.special_tiles.throne;
+                assert_eq!(tables.throne(t), t.row.abs_diff(throne.row) + t.col.abs_diff(throne.col));
+            }
+        }
+    }
+}
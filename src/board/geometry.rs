@@ -1,10 +1,11 @@
+use crate::board::distance::DistanceTables;
 use crate::board::state::BoardState;
 use crate::error::BoardError;
 use crate::tiles::{Coords, Tile, TileIterator};
 
 const NEIGHBOR_OFFSETS: [[i8; 2]; 4] = [[-1, 0], [1, 0], [0, -1], [0, 1]];
 
-#[derive(Copy, Clone, PartialEq, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub struct SpecialTiles {
     pub throne: Tile,
     pub corners: [Tile; 4]
@@ -26,7 +27,7 @@ impl From<u8> for SpecialTiles {
 /// This struct contains information about the geometry of the board, such as its size and the
 /// positions of various special tiles. It does not contain information about piece placement or any
 /// other state that would be expected to change over the course of a game.
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub struct BoardGeometry {
     pub side_len: u8,
     pub special_tiles: SpecialTiles
@@ -120,6 +121,12 @@ impl BoardGeometry {
         self.neighbors(tile).iter().all(|t| state.tile_occupied(*t))
     }
     
+    /// The precomputed distance tables for a board of this size. Panics if the board is larger
+    /// than 21x21.
+    pub fn distances(&self) -> &'static DistanceTables {
+        DistanceTables::for_board(self.side_len)
+    }
+
     /// Return an iterator over all tiles on the board.
     pub fn iter_tiles(&self) -> TileIterator {
         TileIterator::new(self.side_len)
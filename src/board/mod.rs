@@ -1,2 +1,5 @@
+pub mod distance;
 pub mod geometry;
-pub mod state;
\ No newline at end of file
+pub mod render;
+pub mod state;
+pub mod setup;
\ No newline at end of file
@@ -0,0 +1,148 @@
+use crate::board::state::BoardState;
+use crate::pieces::Side;
+use crate::play::PlayRecord;
+use crate::tiles::Tile;
+
+/// The orientation in which a board is rendered.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum Orientation {
+    /// Row 1 at the top and column `a` at the left.
+    #[default]
+    Standard,
+    /// The board rotated by 180 degrees, so row 1 is at the bottom and column `a` at the right.
+    Flipped
+}
+
+impl Orientation {
+
+    /// The orientation in which to show the board to the player of the given side, for example in
+    /// a hot-seat or networked game: standard for the attacker and flipped for the defender.
+    pub fn for_side(side: Side) -> Self {
+        match side {
+            Side::Attacker => Orientation::Standard,
+            Side::Defender => Orientation::Flipped
+        }
+    }
+
+    /// The rows or columns of a board of the given side length, in the order they are displayed.
+    fn order(&self, side_len: u8) -> Vec<u8> {
+        match self {
+            Orientation::Standard => (0..side_len).collect(),
+            Orientation::Flipped => (0..side_len).rev().collect()
+        }
+    }
+}
+
+/// Options for rendering a board as text using [`render`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RenderOptions {
+    /// A play (usually the last one made) to highlight. Its source and destination tiles are
+    /// enclosed in square brackets, eg `[.]` and `[t]`, and the tiles of any pieces it captured
+    /// are enclosed in asterisks, eg `*.*`.
+    pub highlight: Option<PlayRecord>,
+    /// Whether to label the columns with letters and the rows with numbers, as in tile notation.
+    pub labels: bool,
+    /// The orientation in which to render the board. Labels follow the tiles they refer to.
+    pub orientation: Orientation
+}
+
+/// Render the board as text, according to the given options. Each tile is rendered as three
+/// characters: the character for the piece (or `.` for an empty tile) surrounded by any markers
+/// required by the options.
+///
+/// Unlike [`BoardState::to_display_str`], the output of this function cannot be parsed back into
+/// a board.
+pub fn render<B: BoardState>(board: &B, options: &RenderOptions) -> String {
+    let side_len = board.side_len();
+    let label_width = side_len.to_string().len();
+    let order = options.orientation.order(side_len);
+    let mut lines = vec![];
+    if options.labels {
+        let cols: String = order.iter().map(|&c| format!(" {} ", (b'a' + c) as char)).collect();
+        lines.push(format!("{} {cols}", " ".repeat(label_width)).trim_end().to_string());
+    }
+    for &r in &order {
+        let mut line = String::new();
+        if options.labels {
+            line.push_str(&format!("{:>label_width$} ", r + 1));
+        }
+        for &c in &order {
+            let t = Tile::new(r, c);
+            let piece = board.get_piece(t).map_or('.', char::from);
+            let (open, close) = match &options.highlight {
+                Some(record) if t == record.play.from || t == record.play.to() => ('[', ']'),
+                Some(record) if record.effects.captures.iter().any(|p| p.tile == t) => ('*', '*'),
+                _ => (' ', ' ')
+            };
+            line.push(open);
+            line.push(piece);
+            line.push(close);
+        }
+        lines.push(line.trim_end().to_string());
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::render::{render, Orientation, RenderOptions};
+    use crate::pieces::Side::Defender;
+    use crate::game::SmallBasicGame;
+    use crate::play::Play;
+    use crate::preset::rules;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_render() {
+        let mut game = SmallBasicGame::new(rules::BRANDUBH, "7/1K5/7/7/3t3/2tT3/7").unwrap();
+        assert_eq!(
+            render(&game.state.board, &RenderOptions::default()),
+            [
+                " .  .  .  .  .  .  .",
+                " .  K  .  .  .  .  .",
+                " .  .  .  .  .  .  .",
+                " .  .  .  .  .  .  .",
+                " .  .  .  t  .  .  .",
+                " .  .  t  T  .  .  .",
+                " .  .  .  .  .  .  ."
+            ].join("\n")
+        );
+
+        game.do_play(Play::from_str("d5-e5").unwrap()).unwrap();
+        game.do_play(Play::from_str("b2-b3").unwrap()).unwrap();
+        game.do_play(Play::from_str("e5-e6").unwrap()).unwrap();
+        let mut options = RenderOptions {
+            highlight: game.play_history.last().cloned(),
+            labels: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            render(&game.state.board, &options),
+            [
+                "   a  b  c  d  e  f  g",
+                "1  .  .  .  .  .  .  .",
+                "2  .  .  .  .  .  .  .",
+                "3  .  K  .  .  .  .  .",
+                "4  .  .  .  .  .  .  .",
+                "5  .  .  .  . [.] .  .",
+                "6  .  .  t *.*[t] .  .",
+                "7  .  .  .  .  .  .  ."
+            ].join("\n")
+        );
+
+        options.orientation = Orientation::for_side(Defender);
+        assert_eq!(
+            render(&game.state.board, &options),
+            [
+                "   g  f  e  d  c  b  a",
+                "7  .  .  .  .  .  .  .",
+                "6  .  . [t]*.* t  .  .",
+                "5  .  . [.] .  .  .  .",
+                "4  .  .  .  .  .  .  .",
+                "3  .  .  .  .  .  K  .",
+                "2  .  .  .  .  .  .  .",
+                "1  .  .  .  .  .  .  ."
+            ].join("\n")
+        );
+    }
+}
@@ -0,0 +1,272 @@
+use crate::board::state::{BoardState, HugeBasicBoardState};
+use crate::error::SetupError;
+use crate::error::SetupError::{BadBoardSize, BadPieceCount, NotEnoughTiles};
+use crate::game::logic::GameLogic;
+use crate::pieces::{Piece, KING};
+use crate::pieces::PieceType::Soldier;
+use crate::pieces::Side::{Attacker, Defender};
+use crate::tiles::{Coords, Tile};
+use crate::utils::Rng;
+
+/// The kind of symmetry that a randomly generated starting position should have.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Symmetry {
+    /// The position is unchanged by rotating the board by 90 degrees. The king is always placed on
+    /// the central tile, and the number of attackers and of defenders must each be a multiple of
+    /// four.
+    Rotational,
+    /// The position is unchanged by reflecting the board from left to right. The king is placed on
+    /// the central column.
+    Mirror
+}
+
+/// Constraints on a randomly generated starting position.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SetupConstraints {
+    /// The symmetry of the position.
+    pub symmetry: Symmetry,
+    /// The number of attacking pieces.
+    pub n_attackers: u8,
+    /// The number of defending pieces, not including the king.
+    pub n_defenders: u8,
+    /// The maximum number of rows by which the king may be placed away from the centre of the
+    /// board. Only relevant where the symmetry is [`Symmetry::Mirror`].
+    pub max_king_offset: u8,
+    /// The maximum distance (in rows or columns) of any defender from the king.
+    pub defender_radius: u8,
+    /// The minimum distance (in rows or columns) of any attacker from the king.
+    pub attacker_min_distance: u8
+}
+
+/// Return all tiles to which the given tile is mapped by the given symmetry (including the tile
+/// itself), without duplicates.
+fn orbit(tile: Tile, symmetry: Symmetry, side_len: u8) -> Vec<Tile> {
+    let last = side_len - 1;
+    let images = match symmetry {
+        Symmetry::Rotational => vec![
+            tile,
+            Tile::new(tile.col, last - tile.row),
+            Tile::new(last - tile.row, last - tile.col),
+            Tile::new(last - tile.col, tile.row)
+        ],
+        Symmetry::Mirror => vec![tile, Tile::new(tile.row, last - tile.col)]
+    };
+    let mut orbit = vec![];
+    for t in images {
+        if !orbit.contains(&t) {
+            orbit.push(t);
+        }
+    }
+    orbit
+}
+
+/// The distance between two tiles, in rows or columns (whichever is greater).
+fn distance(t1: Tile, t2: Tile) -> u8 {
+    t1.row.abs_diff(t2.row).max(t1.col.abs_diff(t2.col))
+}
+
+/// Whether some of the given orbits contain exactly `n` tiles in total.
+fn can_fill(orbits: &[Vec<Tile>], n: usize) -> bool {
+    let mut reachable = vec![false; n + 1];
+    reachable[0] = true;
+    for o in orbits {
+        for total in (o.len()..=n).rev() {
+            reachable[total] |= reachable[total - o.len()];
+        }
+    }
+    reachable[n]
+}
+
+/// Choose orbits from `candidates` (which are consumed) containing exactly `n` tiles in total.
+/// Orbits are considered in a random order, and each is taken only if the remaining tiles can
+/// still be filled exactly from the orbits after it (so, for example, an odd number of tiles is
+/// never left to be filled from orbits of even size).
+fn fill(candidates: &mut Vec<Vec<Tile>>, n: u8, rng: &mut impl Rng) -> Result<Vec<Tile>, SetupError> {
+    rng.shuffle(candidates);
+    let mut remaining = n as usize;
+    if !can_fill(candidates, remaining) {
+        return Err(NotEnoughTiles)
+    }
+    let mut taken = vec![false; candidates.len()];
+    let mut chosen = vec![];
+    for (i, o) in candidates.iter().enumerate() {
+        if remaining == 0 {
+            break
+        }
+        if o.len() <= remaining && can_fill(&candidates[i + 1..], remaining - o.len()) {
+            remaining -= o.len();
+            chosen.extend(o.iter().copied());
+            taken[i] = true;
+        }
+    }
+    let mut taken = taken.into_iter();
+    candidates.retain(|_| !taken.next().unwrap_or(false));
+    Ok(chosen)
+}
+
+/// Generate a random but symmetric starting position, subject to the given constraints, for the
+/// rules and board size of the given [`GameLogic`]. Pieces are only placed on tiles they may
+/// occupy under the rules (and never on the throne, other than the king). Randomness is drawn from
+/// the given [`Rng`], so a generator created from the same seed will always produce the same
+/// position. Returns the position as a string in FEN format, suitable for
+/// passing to [`crate::game::Game::new`].
+pub fn random_setup(
+    logic: &GameLogic,
+    constraints: &SetupConstraints,
+    rng: &mut impl Rng
+) -> Result<String, SetupError> {
+    let side_len = logic.board_geo.side_len;
+    if side_len.is_multiple_of(2) {
+        return Err(BadBoardSize)
+    }
+    let symmetry = constraints.symmetry;
+    if symmetry == Symmetry::Rotational
+        && (!constraints.n_attackers.is_multiple_of(4) || !constraints.n_defenders.is_multiple_of(4)) {
+        return Err(BadPieceCount)
+    }
+    let centre = side_len / 2;
+    let throne = logic.board_geo.special_tiles.throne;
+
+    let king = match symmetry {
+        Symmetry::Rotational => Tile::new(centre, centre),
+        Symmetry::Mirror => {
+            let max_offset = constraints.max_king_offset.min(centre);
+            let rows: Vec<u8> = (centre - max_offset..=centre + max_offset)
+                .filter(|r| logic.coords_occupiable(Coords::from(Tile::new(*r, centre)), KING))
+                .collect();
+            if rows.is_empty() {
+                return Err(NotEnoughTiles)
+            }
+            Tile::new(rows[rng.below(rows.len())], centre)
+        }
+    };
+
+    // Collect each orbit of tiles (other than those of the king and throne) once, in a
+    // deterministic order.
+    let mut orbits: Vec<Vec<Tile>> = vec![];
+    for tile in logic.board_geo.iter_tiles() {
+        let o = orbit(tile, symmetry, side_len);
+        if o.contains(&king) || o.contains(&throne) || o.iter().any(|t| *t < tile) {
+            continue
+        }
+        orbits.push(o);
+    }
+    let occupiable = |o: &Vec<Tile>, piece: Piece| {
+        o.iter().all(|t| logic.coords_occupiable(Coords::from(*t), piece))
+    };
+
+    let defender = Piece::new(Soldier, Defender);
+    let mut def_candidates: Vec<Vec<Tile>> = orbits.iter()
+        .filter(|o| o.iter().all(|t| distance(*t, king) <= constraints.defender_radius))
+        .filter(|o| occupiable(o, defender))
+        .cloned()
+        .collect();
+    let defenders = fill(&mut def_candidates, constraints.n_defenders, rng)?;
+
+    let attacker = Piece::new(Soldier, Attacker);
+    let mut att_candidates: Vec<Vec<Tile>> = orbits.into_iter()
+        .filter(|o| o.iter().all(|t|
+            distance(*t, king) >= constraints.attacker_min_distance && !defenders.contains(t)
+        ))
+        .filter(|o| occupiable(o, attacker))
+        .collect();
+    let attackers = fill(&mut att_candidates, constraints.n_attackers, rng)?;
+
+    let empty = vec![side_len.to_string(); side_len as usize].join("/");
+    let mut board = HugeBasicBoardState::from_fen(&empty)
+        .expect("Empty board should be valid FEN.");
+    board.set_piece(king, KING);
+    for t in defenders {
+        board.set_piece(t, defender);
+    }
+    for t in attackers {
+        board.set_piece(t, attacker);
+    }
+    Ok(board.to_fen())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::setup::{orbit, random_setup, SetupConstraints, Symmetry};
+    use crate::board::state::{BoardState, MediumBasicBoardState};
+    use crate::error::SetupError::{BadPieceCount, NotEnoughTiles};
+    use crate::game::logic::GameLogic;
+    use crate::game::MediumBasicGame;
+    use crate::pieces::Side::{Attacker, Defender};
+    use crate::preset::rules;
+    use crate::tiles::Tile;
+    use crate::utils::SplitMix64;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_random_setup() {
+        let logic = GameLogic::new(rules::COPENHAGEN, 11);
+        let constraints = SetupConstraints {
+            symmetry: Symmetry::Rotational,
+            n_attackers: 24,
+            n_defenders: 12,
+            max_king_offset: 0,
+            defender_radius: 3,
+            attacker_min_distance: 3
+        };
+        let fen = random_setup(&logic, &constraints, &mut SplitMix64::new(42)).unwrap();
+        assert_eq!(random_setup(&logic, &constraints, &mut SplitMix64::new(42)).unwrap(), fen);
+        assert_ne!(random_setup(&logic, &constraints, &mut SplitMix64::new(43)).unwrap(), fen);
+        let board = MediumBasicBoardState::from_str(&fen).unwrap();
+        assert_eq!(board.count_pieces(Attacker), 24);
+        assert_eq!(board.count_pieces(Defender), 13);
+        assert_eq!(board.get_king(), Tile::new(5, 5));
+        for t in logic.board_geo.iter_tiles() {
+            for image in orbit(t, Symmetry::Rotational, 11) {
+                assert_eq!(board.get_piece(t), board.get_piece(image));
+            }
+        }
+        for t in logic.board_geo.special_tiles.corners {
+            assert!(!board.tile_occupied(t));
+        }
+        assert!(MediumBasicGame::new(rules::COPENHAGEN, &fen).is_ok());
+
+        let constraints = SetupConstraints {
+            symmetry: Symmetry::Mirror,
+            n_attackers: 23,
+            n_defenders: 9,
+            max_king_offset: 2,
+            ..constraints
+        };
+        for seed in 0..20 {
+            let board = MediumBasicBoardState::from_str(
+                &random_setup(&logic, &constraints, &mut SplitMix64::new(seed)).unwrap()
+            ).unwrap();
+            assert_eq!(board.count_pieces(Attacker), 23);
+            assert_eq!(board.count_pieces(Defender), 10);
+            let king = board.get_king();
+            assert_eq!(king.col, 5);
+            assert!(king.row.abs_diff(5) <= 2);
+            for t in logic.board_geo.iter_tiles() {
+                assert_eq!(board.get_piece(t), board.get_piece(Tile::new(t.row, 10 - t.col)));
+            }
+        }
+
+        // With an odd number of defenders close to the king, one of the few orbits on the king's
+        // column must be kept for the last defender.
+        let tight = SetupConstraints {
+            n_attackers: 9,
+            n_defenders: 3,
+            max_king_offset: 0,
+            defender_radius: 1,
+            ..constraints
+        };
+        for seed in 0..200 {
+            let board = MediumBasicBoardState::from_str(
+                &random_setup(&logic, &tight, &mut SplitMix64::new(seed)).unwrap()
+            ).unwrap();
+            assert_eq!(board.count_pieces(Defender), 4);
+            assert_eq!(board.count_pieces(Attacker), 9);
+        }
+
+        let bad_count = SetupConstraints { symmetry: Symmetry::Rotational, n_defenders: 10, ..constraints };
+        assert_eq!(random_setup(&logic, &bad_count, &mut SplitMix64::new(0)), Err(BadPieceCount));
+        let too_many = SetupConstraints { n_defenders: 60, ..constraints };
+        assert_eq!(random_setup(&logic, &too_many, &mut SplitMix64::new(0)), Err(NotEnoughTiles));
+    }
+}
@@ -5,9 +5,9 @@ use std::str::FromStr;
 use crate::bitfield::BitField;
 use crate::error::ParseError;
 use crate::error::ParseError::BadLineLen;
-use crate::pieces::{Piece, Side};
-use crate::pieces::PieceType::{King, Soldier};
-use crate::tiles::Tile;
+use crate::pieces::{Piece, PieceType, Side};
+use crate::pieces::PieceType::{Commander, Guard, King, Knight, Mercenary, Soldier};
+use crate::tiles::{Tile, TileSet};
 
 /// Store information on the current board state (ie, pieces).
 pub trait BoardState: Default + Clone + Copy + Display + FromStr + Debug + PartialEq {
@@ -65,6 +65,26 @@ pub trait BoardState: Default + Clone + Copy + Display + FromStr + Debug + Parti
     /// Return the length of the board's side.
     fn side_len(&self) -> u8;
 
+    /// Whether pieces of the given type can be stored on this type of board.
+    fn supports_piece_type(piece_type: PieceType) -> bool;
+
+    /// The set of tiles occupied by pieces of the given side (including the king, for the
+    /// defender).
+    fn occupied(&self, side: Side) -> TileSet {
+        self.iter_occupied(side).collect()
+    }
+
+    /// The set containing the tile occupied by the king, or an empty set if the king is not on the
+    /// board.
+    fn king_occupancy(&self) -> TileSet {
+        let king = self.get_king();
+        let mut set = TileSet::new();
+        if self.get_piece(king).is_some_and(|p| p.piece_type == King) {
+            set.insert(king);
+        }
+        set
+    }
+
     /// Swap the pieces at two positions.
     fn swap_pieces(&mut self, t1: Tile, t2: Tile) {
         let p1 = self.get_piece(t1);
@@ -80,6 +100,104 @@ pub trait BoardState: Default + Clone + Copy + Display + FromStr + Debug + Parti
     
 }
 
+/// Place the pieces described by (the relevant part of) a string in FEN format on the given board.
+/// Returns the length of the board's side.
+fn place_fen_pieces<B: BoardState>(fen: &str, board: &mut B) -> Result<u8, ParseError> {
+    let mut side_len = 0;
+    for (r, line) in fen.split('/').enumerate() {
+        let mut n_empty = 0;
+        let mut c = 0u8;
+        for chr in line.chars() {
+            if chr.is_digit(10) {
+                n_empty = (n_empty * 10) + (chr as u8 - '0' as u8);
+            } else {
+                c += n_empty;
+                n_empty = 0;
+                board.set_piece(Tile::new(r as u8, c), Piece::try_from(chr)?);
+                c += 1;
+            }
+        }
+        if n_empty > 0 {
+            c += n_empty;
+        }
+        if side_len == 0 {
+            side_len = c;
+        } else if side_len != c {
+            return Err(BadLineLen(c as usize))
+        }
+    }
+    Ok(side_len)
+}
+
+/// Place the pieces described by a string in the format output by
+/// [`BoardState::to_display_str`] on the given board. Returns the length of the board's side.
+fn place_display_str_pieces<B: BoardState>(
+    display_str: &str,
+    board: &mut B
+) -> Result<u8, ParseError> {
+    let s = display_str.trim();
+    let mut side_len = 0;
+    for (r, line) in s.lines().enumerate() {
+        let line_len = line.len() as u8;
+        if side_len == 0 {
+            side_len = line_len
+        } else if line_len != side_len {
+            return Err(BadLineLen(line.len()))
+        }
+        for (c, chr) in line.chars().enumerate() {
+            if chr != '.' {
+                board.set_piece(Tile::new(r as u8, c as u8), Piece::try_from(chr)?)
+            }
+        }
+    }
+    Ok(side_len)
+}
+
+/// Return a string in FEN format representing the given board.
+fn board_to_fen<B: BoardState>(board: &B) -> String {
+    let side_len = board.side_len();
+    let mut s = String::new();
+    for row in 0..side_len {
+        let mut n_empty = 0;
+        for col in 0..side_len {
+            let t = Tile::new(row, col);
+            if let Some(piece) = board.get_piece(t) {
+                if n_empty > 0 {
+                    s.push_str(n_empty.to_string().as_str());
+                    n_empty = 0;
+                }
+                s.push(piece.into());
+            } else {
+                n_empty += 1;
+            }
+        }
+        if n_empty > 0 {
+            s.push_str(n_empty.to_string().as_str());
+        }
+        if row < side_len - 1 {
+            s.push('/');
+        }
+    }
+    s
+}
+
+/// Return a string representing the given board, in a format suitable for printing.
+fn board_to_display_str<B: BoardState>(board: &B) -> String {
+    let side_len = board.side_len();
+    let mut s = String::new();
+    for r in 0..side_len {
+        for c in 0..side_len {
+            let t = Tile::new(r, c);
+            let p = board.get_piece(t);
+            match p {
+                Some(piece) => s.push(piece.into()),
+                None => s.push('.'),
+            }
+        }
+        s.push('\n');
+    }
+    s
+}
 
 pub struct BitfieldIter<T: BitField> {
     /// Bitfield representing board state.
@@ -102,7 +220,8 @@ impl<T: BitField> Iterator for BitfieldIter<T> {
 }
 
 /// Store information on the current board state (ie, pieces) using bitfields. This struct currently
-/// handles only a simple board, ie, a king and soldiers (no knights, commanders, etc).
+/// handles only a simple board, ie, a king and soldiers (no knights, commanders, etc). See
+/// [`ExtendedBitfieldBoardState`] for a board state that supports all piece types.
 ///
 /// The parameter `T` is a type that implements the [`BitField`] trait, ensuring that it supports
 /// the relevant bitwise operations.  A single integer of type `T` is used to record the positions
@@ -120,6 +239,36 @@ pub struct BitfieldBoardState<T: BitField> {
     side_len: u8
 }
 
+impl<T: BitField> BitfieldBoardState<T> {
+
+    /// Clear the bits of the given bitfield which are used to encode the position of the king.
+    fn without_king_bits(field: T) -> T {
+        let mut bytes = field.to_be_bytes();
+        let bytes_slice = bytes.as_mut();
+        bytes_slice[0] &= 0b0000_1111;  // Unset 4 most significant bits
+        T::from_be_bytes_slice(bytes_slice)
+    }
+
+    /// The raw bitfield recording the tiles occupied by attacking pieces. The bit for a tile is
+    /// given by [`BitField::tile_mask`].
+    pub fn attacker_bits(&self) -> T {
+        Self::without_king_bits(self.attackers)
+    }
+
+    /// The raw bitfield recording the tiles occupied by defending pieces, including the king. The
+    /// bit for a tile is given by [`BitField::tile_mask`].
+    pub fn defender_bits(&self) -> T {
+        Self::without_king_bits(self.defenders)
+    }
+
+    /// The raw bitfield with only the bit for the king's tile set, or no bits if the king is not on
+    /// the board.
+    pub fn king_bits(&self) -> T {
+        let mask = T::tile_mask(self.get_king());
+        self.defender_bits() & mask
+    }
+}
+
 impl<T: BitField> BoardState for BitfieldBoardState<T> {
     
     type Iter = BitfieldIter<T>;
@@ -204,13 +353,8 @@ impl<T: BitField> BoardState for BitfieldBoardState<T> {
             Side::Attacker => self.attackers,
             Side::Defender => self.defenders
         };
-        // unset bits which encode the position of the king
-        let mut state_bytes = state_with_king.to_be_bytes();
-        let state_bytes_slice = state_bytes.as_mut();
-        state_bytes_slice[0] &= 0b0000_1111;  // Unset 4 most significant bits
-        let state = T::from_be_bytes_slice(state_bytes_slice);
         Self::Iter {
-            state,
+            state: Self::without_king_bits(state_with_king),
             i: 0
         }
     }
@@ -224,95 +368,31 @@ impl<T: BitField> BoardState for BitfieldBoardState<T> {
 
     fn from_fen(fen: &str) -> Result<Self, ParseError> {
         let mut state = Self::default();
-        for (r, line) in fen.split('/').enumerate() {
-            let mut n_empty = 0;
-            let mut c = 0u8;
-            for chr in line.chars() {
-                if chr.is_digit(10) {
-                    n_empty = (n_empty * 10) + (chr as u8 - '0' as u8);
-                } else {
-                    c += n_empty;
-                    n_empty = 0;
-                    state.set_piece(Tile::new(r as u8, c), Piece::try_from(chr)?);
-                    c += 1;
-                }
-            }
-            if n_empty > 0 {
-                c += n_empty;
-            }
-            if state.side_len == 0 {
-                state.side_len = c;
-            } else if state.side_len != c {
-                return Err(BadLineLen(c as usize))
-            }
-        }
+        state.side_len = place_fen_pieces(fen, &mut state)?;
         Ok(state)
     }
 
     fn from_display_str(display_str: &str) -> Result<Self, ParseError> {
-        let s = display_str.trim();
         let mut state = Self::default();
-        for (r, line) in s.lines().enumerate() {
-            let line_len = line.len() as u8;
-            if state.side_len == 0 {
-                state.side_len = line_len
-            } else if line_len != state.side_len {
-                return Err(BadLineLen(line.len()))
-            }
-            for (c, chr) in line.chars().enumerate() {
-                if chr != '.' {
-                    state.set_piece(Tile::new(r as u8, c as u8), Piece::try_from(chr)?)
-                }
-            }
-        }
+        state.side_len = place_display_str_pieces(display_str, &mut state)?;
         Ok(state)
     }
 
     fn to_fen(&self) -> String {
-        let mut s = String::new();
-        for row in 0..self.side_len {
-            let mut n_empty = 0;
-            for col in 0..self.side_len {
-                let t = Tile::new(row, col);
-                if let Some(piece) = self.get_piece(t) {
-                    if n_empty > 0 {
-                        s.push_str(n_empty.to_string().as_str());
-                        n_empty = 0;
-                    }
-                    s.push(piece.into());
-                } else {
-                    n_empty += 1;
-                }
-            }
-            if n_empty > 0 {
-                s.push_str(n_empty.to_string().as_str());
-            }
-            if row < self.side_len - 1 {
-                s.push('/');
-            }
-        }
-        s
+        board_to_fen(self)
     }
 
     fn to_display_str(&self) -> String {
-        let mut s = String::new();
-        for r in 0..self.side_len {
-            for c in 0..self.side_len {
-                let t = Tile::new(r, c);
-                let p = self.get_piece(t);
-                match p {
-                    Some(piece) => s.push(piece.into()),
-                    None => s.push('.'),
-                }
-            }
-            s.push('\n');
-        }
-        s
+        board_to_display_str(self)
     }
 
     fn side_len(&self) -> u8 {
         self.side_len
     }
+
+    fn supports_piece_type(piece_type: PieceType) -> bool {
+        matches!(piece_type, King | Soldier)
+    }
 }
 
 impl<T: BitField> FromStr for BitfieldBoardState<T> {
@@ -328,6 +408,187 @@ impl <T: BitField> Display for BitfieldBoardState<T> {
     }
 }
 
+/// Store information on the current board state using bitfields, supporting all piece types
+/// (knights, commanders, guards and mercenaries as well as soldiers and the king).
+///
+/// This wraps a [`BitfieldBoardState`], which records which side (if any) occupies each tile and
+/// the position of the king, and adds a bitfield for each additional piece type recording which
+/// tiles are occupied by pieces of that type. Any non-king piece not recorded in one of those
+/// bitfields is a soldier.
+#[derive(Copy, Clone, Hash, Eq, PartialEq, Default, Debug)]
+pub struct ExtendedBitfieldBoardState<T: BitField> {
+    base: BitfieldBoardState<T>,
+    knights: T,
+    commanders: T,
+    guards: T,
+    mercenaries: T
+}
+
+impl<T: BitField> ExtendedBitfieldBoardState<T> {
+
+    /// Get a mutable reference to the bitfield recording the positions of pieces of the given type,
+    /// if there is one (there is none for soldiers or the king).
+    fn type_field_mut(&mut self, piece_type: PieceType) -> Option<&mut T> {
+        match piece_type {
+            Knight => Some(&mut self.knights),
+            Commander => Some(&mut self.commanders),
+            Guard => Some(&mut self.guards),
+            Mercenary => Some(&mut self.mercenaries),
+            King | Soldier => None
+        }
+    }
+
+    /// Get the type of the non-king piece (assumed to be) at the given tile.
+    fn non_king_type(&self, t: Tile) -> PieceType {
+        let mask = T::tile_mask(t);
+        for (field, piece_type) in [
+            (self.knights, Knight),
+            (self.commanders, Commander),
+            (self.guards, Guard),
+            (self.mercenaries, Mercenary)
+        ] {
+            if (field & mask) > 0.into() {
+                return piece_type
+            }
+        }
+        Soldier
+    }
+
+    /// The raw bitfield recording the tiles occupied by attacking pieces. See
+    /// [`BitfieldBoardState::attacker_bits`].
+    pub fn attacker_bits(&self) -> T {
+        self.base.attacker_bits()
+    }
+
+    /// The raw bitfield recording the tiles occupied by defending pieces, including the king. See
+    /// [`BitfieldBoardState::defender_bits`].
+    pub fn defender_bits(&self) -> T {
+        self.base.defender_bits()
+    }
+
+    /// The raw bitfield with only the bit for the king's tile set. See
+    /// [`BitfieldBoardState::king_bits`].
+    pub fn king_bits(&self) -> T {
+        self.base.king_bits()
+    }
+
+    /// The raw bitfield recording the tiles occupied by pieces of the given type (of either side).
+    pub fn piece_type_bits(&self, piece_type: PieceType) -> T {
+        match piece_type {
+            Knight => self.knights,
+            Commander => self.commanders,
+            Guard => self.guards,
+            Mercenary => self.mercenaries,
+            King => self.king_bits(),
+            Soldier => (self.attacker_bits() | self.defender_bits())
+                & !(self.knights | self.commanders | self.guards | self.mercenaries | self.king_bits())
+        }
+    }
+
+    /// Unset the bit corresponding to the given tile in each of the piece type bitfields.
+    fn clear_type_fields(&mut self, t: Tile) {
+        let mask = !T::tile_mask(t);
+        self.knights &= mask;
+        self.commanders &= mask;
+        self.guards &= mask;
+        self.mercenaries &= mask;
+    }
+}
+
+impl<T: BitField> BoardState for ExtendedBitfieldBoardState<T> {
+
+    type Iter = BitfieldIter<T>;
+
+    fn get_king(&self) -> Tile {
+        self.base.get_king()
+    }
+
+    fn set_king(&mut self, t: Tile) {
+        self.base.set_king(t)
+    }
+
+    fn set_piece(&mut self, t: Tile, piece: Piece) {
+        self.clear_type_fields(t);
+        self.base.set_piece(t, piece);
+        if let Some(field) = self.type_field_mut(piece.piece_type) {
+            *field |= T::tile_mask(t);
+        }
+    }
+
+    fn clear_tile(&mut self, t: Tile) {
+        self.base.clear_tile(t);
+        self.clear_type_fields(t);
+    }
+
+    fn get_piece(&self, t: Tile) -> Option<Piece> {
+        self.base.get_piece(t).map(|p| if p.piece_type == King {
+            p
+        } else {
+            Piece::new(self.non_king_type(t), p.side)
+        })
+    }
+
+    fn tile_occupied(&self, t: Tile) -> bool {
+        self.base.tile_occupied(t)
+    }
+
+    fn count_pieces(&self, side: Side) -> u8 {
+        self.base.count_pieces(side)
+    }
+
+    fn iter_occupied(&self, side: Side) -> Self::Iter {
+        self.base.iter_occupied(side)
+    }
+
+    fn move_piece(&mut self, from: Tile, to: Tile) -> Piece {
+        let piece = self.get_piece(from).expect("No piece to move.");
+        self.set_piece(to, piece);
+        self.clear_tile(from);
+        piece
+    }
+
+    fn from_fen(fen: &str) -> Result<Self, ParseError> {
+        let mut state = Self::default();
+        state.base.side_len = place_fen_pieces(fen, &mut state)?;
+        Ok(state)
+    }
+
+    fn from_display_str(display_str: &str) -> Result<Self, ParseError> {
+        let mut state = Self::default();
+        state.base.side_len = place_display_str_pieces(display_str, &mut state)?;
+        Ok(state)
+    }
+
+    fn to_fen(&self) -> String {
+        board_to_fen(self)
+    }
+
+    fn to_display_str(&self) -> String {
+        board_to_display_str(self)
+    }
+
+    fn side_len(&self) -> u8 {
+        self.base.side_len
+    }
+
+    fn supports_piece_type(_piece_type: PieceType) -> bool {
+        true
+    }
+}
+
+impl<T: BitField> FromStr for ExtendedBitfieldBoardState<T> {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_fen(s)
+    }
+}
+
+impl <T: BitField> Display for ExtendedBitfieldBoardState<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_display_str())
+    }
+}
+
 /// Board state supporting basic pieces (soldier and king), suitable for boards up to 7x7.
 pub type SmallBasicBoardState = BitfieldBoardState<u64>;
 /// Board state supporting basic pieces (soldier and king), suitable for boards up to 11x11.
@@ -339,14 +600,24 @@ pub type LargeBasicBoardState = BitfieldBoardState<U256>;
 /// Board state supporting basic pieces (soldier and king), suitable for boards up to 21x21.
 pub type HugeBasicBoardState = BitfieldBoardState<U512>;
 
+/// Board state supporting all piece types, suitable for boards up to 7x7.
+pub type SmallExtendedBoardState = ExtendedBitfieldBoardState<u64>;
+/// Board state supporting all piece types, suitable for boards up to 11x11.
+pub type MediumExtendedBoardState = ExtendedBitfieldBoardState<u128>;
+/// Board state supporting all piece types, suitable for boards up to 15x15.
+pub type LargeExtendedBoardState = ExtendedBitfieldBoardState<U256>;
+/// Board state supporting all piece types, suitable for boards up to 21x21.
+pub type HugeExtendedBoardState = ExtendedBitfieldBoardState<U512>;
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
     use std::str::FromStr;
-    use crate::board::state::{BoardState, MediumBasicBoardState, SmallBasicBoardState};
+    use crate::board::state::{BoardState, MediumBasicBoardState, SmallBasicBoardState, SmallExtendedBoardState};
     use crate::pieces::Piece;
-    use crate::pieces::PieceType::{King, Soldier};
+    use crate::pieces::PieceType::{Commander, Guard, King, Knight, Soldier};
     use crate::pieces::Side::{Attacker, Defender};
+    use crate::bitfield::BitField;
     use crate::preset::boards;
     use crate::tiles::Tile;
 
@@ -447,4 +718,54 @@ mod tests {
         assert_eq!(board.count_pieces(Attacker), 24);
         assert_eq!(board.count_pieces(Defender), 13);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_extended_board_state() {
+        let fen = "3n3/3t3/3G3/ttCKGtt/3T3/3c3/3t3";
+        let mut board = SmallExtendedBoardState::from_str(fen).unwrap();
+        assert_eq!(board.to_fen(), fen);
+        assert_eq!(board.get_piece(Tile::new(0, 3)), Some(Piece::new(Knight, Attacker)));
+        assert_eq!(board.get_piece(Tile::new(3, 2)), Some(Piece::new(Commander, Defender)));
+        assert_eq!(board.get_piece(Tile::new(3, 3)), Some(Piece::new(King, Defender)));
+        assert_eq!(board.get_piece(Tile::new(4, 3)), Some(Piece::new(Soldier, Defender)));
+        assert_eq!(board.count_pieces(Attacker), 8);
+        assert_eq!(board.count_pieces(Defender), 5);
+
+        assert_eq!(board.move_piece(Tile::new(5, 3), Tile::new(5, 0)), Piece::new(Commander, Attacker));
+        assert_eq!(board.get_piece(Tile::new(5, 0)), Some(Piece::new(Commander, Attacker)));
+        assert_eq!(board.get_piece(Tile::new(5, 3)), None);
+        board.set_piece(Tile::new(2, 3), Piece::new(Soldier, Attacker));
+        assert_eq!(board.get_piece(Tile::new(2, 3)), Some(Piece::new(Soldier, Attacker)));
+        board.clear_tile(Tile::new(3, 4));
+        board.set_piece(Tile::new(3, 4), Piece::new(Soldier, Defender));
+        assert_eq!(board.get_piece(Tile::new(3, 4)), Some(Piece::new(Soldier, Defender)));
+        assert_eq!(board.get_piece(Tile::new(2, 4)), None);
+        board.set_piece(Tile::new(2, 4), Piece::new(Guard, Attacker));
+        assert_eq!(board.get_piece(Tile::new(2, 4)), Some(Piece::new(Guard, Attacker)));
+    }
+
+    #[test]
+    fn test_occupancy() {
+        let state = SmallBasicBoardState::from_fen(boards::BRANDUBH).unwrap();
+        let attackers = state.occupied(Attacker);
+        assert_eq!(attackers.len(), 8);
+        assert!(attackers.contains(Tile::new(0, 3)));
+        assert_eq!(state.occupied(Defender).len(), 5);
+        assert_eq!(state.king_occupancy().iter().collect::<Vec<_>>(), vec![Tile::new(3, 3)]);
+        assert_eq!(state.attacker_bits().count_ones(), 8);
+        assert_eq!(state.defender_bits().count_ones(), 5);
+        assert_eq!(state.king_bits(), u64::tile_mask(Tile::new(3, 3)));
+        assert_eq!(state.attacker_bits() & state.defender_bits(), 0);
+
+        let state = SmallBasicBoardState::from_fen("3t3/7/7/7/7/7/3T3").unwrap();
+        assert!(state.king_occupancy().is_empty());
+        assert_eq!(state.king_bits(), 0);
+
+        let state = SmallExtendedBoardState::from_fen("3t3/1G5/7/3K3/7/7/3n3").unwrap();
+        assert_eq!(state.piece_type_bits(Guard), u64::tile_mask(Tile::new(1, 1)));
+        assert_eq!(state.piece_type_bits(Knight), u64::tile_mask(Tile::new(6, 3)));
+        assert_eq!(state.piece_type_bits(Soldier), u64::tile_mask(Tile::new(0, 3)));
+        assert_eq!(state.piece_type_bits(King), u64::tile_mask(Tile::new(3, 3)));
+        assert_eq!(state.occupied(Defender).len(), 2);
+    }
+}
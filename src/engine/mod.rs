@@ -0,0 +1,161 @@
+use crate::board::state::BoardState;
+use crate::game::Game;
+use crate::play::Play;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+pub mod runner;
+pub mod tournament;
+#[cfg(test)]
+pub(crate) mod test_utils;
+
+/// A play chosen by an [`Engine`], together with the engine's evaluation of the position.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct EngineMove {
+    /// The play to make.
+    pub play: Play,
+    /// The engine's evaluation of the position, from the attacker's perspective (positive values
+    /// favour the attacker), if it provides one.
+    pub eval: Option<f64>
+}
+
+/// A player (typically an AI) which can choose plays in a game.
+pub trait Engine<T: BoardState> {
+
+    /// Choose a play for the side to play in the given game, which is still ongoing.
+    fn choose_play(&mut self, game: &Game<T>) -> EngineMove;
+}
+
+/// A token which can be used to ask an [`AsyncEngine`] to stop thinking. Clones of a token share
+/// the same state, so one clone can be passed to the engine and another kept to cancel it (from
+/// any thread).
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+
+    /// Create a new token, which has not been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask any engine holding a clone of this token to stop.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    /// Whether the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+/// An asynchronous variant of [`Engine`], for engines which do I/O while choosing a play (for
+/// example, querying a remote engine or inference service). This does not depend on any
+/// particular async runtime.
+pub trait AsyncEngine<T: BoardState> {
+
+    /// Choose a play for the side to play in the given game, which is still ongoing. The engine
+    /// should check `cancel` regularly (or whenever it awaits) and return `None` as soon as
+    /// possible once it has been cancelled.
+    fn choose_play(
+        &mut self,
+        game: &Game<T>,
+        cancel: &CancelToken
+    ) -> impl Future<Output = Option<EngineMove>> + Send;
+}
+
+/// Wraps a synchronous [`Engine`] so that it can be used where an [`AsyncEngine`] is expected.
+/// The wrapped engine runs to completion as soon as `choose_play` is called, so it should be fast
+/// or run on a thread where blocking is acceptable. It only checks for cancellation before
+/// starting.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Blocking<E>(pub E);
+
+impl<T: BoardState + Sync, E: Engine<T> + Send> AsyncEngine<T> for Blocking<E> {
+    fn choose_play(
+        &mut self,
+        game: &Game<T>,
+        cancel: &CancelToken
+    ) -> impl Future<Output = Option<EngineMove>> + Send {
+        let result = if cancel.is_cancelled() {
+            None
+        } else {
+            Some(self.0.choose_play(game))
+        };
+        std::future::ready(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::state::SmallBasicBoardState;
+    use crate::engine::test_utils::FirstPlay;
+    use crate::engine::{AsyncEngine, Blocking, CancelToken, EngineMove};
+    use crate::game::{Game, SmallBasicGame};
+    use crate::preset::{boards, rules};
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake};
+
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    /// Poll the given future until it completes, without any async runtime.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Arc::new(NoopWaker).into();
+        let mut cx = Context::from_waker(&waker);
+        let mut future = pin!(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output
+            }
+        }
+    }
+
+    /// Waits for a number of polls before answering, checking for cancellation each time.
+    struct Slow(usize);
+
+    impl AsyncEngine<SmallBasicBoardState> for Slow {
+        async fn choose_play(
+            &mut self,
+            game: &Game<SmallBasicBoardState>,
+            cancel: &CancelToken
+        ) -> Option<EngineMove> {
+            for _ in 0..self.0 {
+                if cancel.is_cancelled() {
+                    return None
+                }
+                let mut yielded = false;
+                std::future::poll_fn(|_| if yielded {
+                    Poll::Ready(())
+                } else {
+                    yielded = true;
+                    Poll::Pending
+                }).await;
+            }
+            Some(EngineMove { play: game.valid_plays()[0].play, eval: Some(0.0) })
+        }
+    }
+
+    #[test]
+    fn test_async_engine() {
+        let game = SmallBasicGame::new(rules::BRANDUBH, boards::BRANDUBH).unwrap();
+        let cancel = CancelToken::new();
+        let expected = game.valid_plays()[0].play;
+        let chosen = block_on(Blocking(FirstPlay).choose_play(&game, &cancel));
+        assert_eq!(chosen.map(|m| m.play), Some(expected));
+        assert_eq!(block_on(Slow(3).choose_play(&game, &cancel)).map(|m| m.play), Some(expected));
+
+        let remote = cancel.clone();
+        remote.cancel();
+        assert!(cancel.is_cancelled());
+        assert_eq!(block_on(Blocking(FirstPlay).choose_play(&game, &cancel)), None);
+        assert_eq!(block_on(Slow(3).choose_play(&game, &cancel)), None);
+    }
+}
@@ -0,0 +1,217 @@
+use crate::board::state::BoardState;
+use crate::engine::Engine;
+use crate::error::PlayInvalid;
+use crate::game::GameOutcome::{Draw, Win};
+use crate::game::GameStatus::{Ongoing, Over};
+use crate::game::{DrawReason, Game, GameOutcome, WinReason};
+use crate::pieces::Side::{Attacker, Defender};
+
+/// Thresholds for ending a game between engines early, so that long runs of games (for example,
+/// when tuning an engine) do not spend time playing out positions whose result is clear.
+///
+/// Evaluations are from the attacker's perspective, as in [`EngineMove`](crate::engine::EngineMove).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AdjudicationRules {
+    /// The game is awarded to a side once both engines' evaluations favour it by at least this
+    /// much for `win_moves` consecutive moves each. `None` disables win adjudication.
+    pub win_threshold: Option<f64>,
+    /// The number of consecutive moves by each engine required for win adjudication.
+    pub win_moves: usize,
+    /// The game is drawn once both engines' evaluations are within this much of zero for
+    /// `draw_moves` consecutive moves each. `None` disables draw adjudication.
+    pub draw_threshold: Option<f64>,
+    /// The number of consecutive moves by each engine required for draw adjudication.
+    pub draw_moves: usize,
+    /// Draws are not adjudicated before this many plays have been made.
+    pub draw_min_plays: usize
+}
+
+impl Default for AdjudicationRules {
+    fn default() -> Self {
+        Self {
+            win_threshold: None,
+            win_moves: 4,
+            draw_threshold: None,
+            draw_moves: 8,
+            draw_min_plays: 40
+        }
+    }
+}
+
+/// Tracks the evaluations reported by engines over the course of a game and decides when the game
+/// should be adjudicated under some [`AdjudicationRules`]. A play for which no evaluation is
+/// reported interrupts any streak.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Adjudicator {
+    rules: AdjudicationRules,
+    win_streak: usize,
+    win_sign: f64,
+    draw_streak: usize
+}
+
+impl Adjudicator {
+
+    /// Create a new adjudicator applying the given rules.
+    pub fn new(rules: AdjudicationRules) -> Self {
+        Self { rules, win_streak: 0, win_sign: 0.0, draw_streak: 0 }
+    }
+
+    /// Record the evaluation reported with a play, where `plays` is the number of plays made in
+    /// the game so far (including this one). Returns the outcome to which the game should be
+    /// adjudicated, if any.
+    pub fn observe(&mut self, eval: Option<f64>, plays: usize) -> Option<GameOutcome> {
+        let Some(eval) = eval else {
+            self.win_streak = 0;
+            self.draw_streak = 0;
+            return None
+        };
+
+        match self.rules.win_threshold {
+            Some(threshold) if eval.abs() >= threshold => {
+                if eval.signum() == self.win_sign {
+                    self.win_streak += 1;
+                } else {
+                    self.win_sign = eval.signum();
+                    self.win_streak = 1;
+                }
+            },
+            _ => self.win_streak = 0
+        }
+        match self.rules.draw_threshold {
+            Some(threshold) if eval.abs() <= threshold => self.draw_streak += 1,
+            _ => self.draw_streak = 0
+        }
+
+        // The engines alternate, so a streak of 2n plays includes n moves by each.
+        if self.win_streak > 0 && self.win_streak >= 2 * self.rules.win_moves {
+            let winner = if self.win_sign > 0.0 { Attacker } else { Defender };
+            return Some(Win(WinReason::Adjudicated, winner))
+        }
+        if self.draw_streak > 0
+            && self.draw_streak >= 2 * self.rules.draw_moves
+            && plays >= self.rules.draw_min_plays {
+            return Some(Draw(DrawReason::Adjudicated))
+        }
+        None
+    }
+}
+
+/// The result of a game played by [`run_game`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineGameResult {
+    /// The outcome of the game, or `None` if it was stopped after the maximum number of plays.
+    pub outcome: Option<GameOutcome>,
+    /// Whether the outcome was decided by adjudication.
+    pub adjudicated: bool,
+    /// The evaluation reported with each play, in order.
+    pub evals: Vec<Option<f64>>
+}
+
+/// Play the given game to its conclusion between two engines, stopping after `max_plays` plays
+/// (in total) or when the game is adjudicated under the given rules, if any.
+///
+/// Returns an error if an engine chooses an invalid play.
+pub fn run_game<T: BoardState>(
+    game: &mut Game<T>,
+    attacker: &mut dyn Engine<T>,
+    defender: &mut dyn Engine<T>,
+    max_plays: usize,
+    adjudication: Option<AdjudicationRules>
+) -> Result<EngineGameResult, PlayInvalid> {
+    let mut adjudicator = adjudication.map(Adjudicator::new);
+    let mut evals = vec![];
+    while game.state.status == Ongoing && evals.len() < max_plays {
+        let engine_move = match game.state.side_to_play {
+            Attacker => attacker.choose_play(game),
+            Defender => defender.choose_play(game)
+        };
+        game.do_play(engine_move.play)?;
+        evals.push(engine_move.eval);
+        if game.state.status != Ongoing {
+            break
+        }
+        let adjudicated = adjudicator.as_mut()
+            .and_then(|a| a.observe(engine_move.eval, game.play_history.len()));
+        if let Some(outcome) = adjudicated {
+            game.end(outcome);
+            return Ok(EngineGameResult { outcome: Some(outcome), adjudicated: true, evals })
+        }
+    }
+    let outcome = match game.state.status {
+        Over(outcome) => Some(outcome),
+        Ongoing => None
+    };
+    Ok(EngineGameResult { outcome, adjudicated: false, evals })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::state::SmallBasicBoardState;
+    use crate::engine::runner::{run_game, AdjudicationRules, Adjudicator};
+    use crate::engine::{Engine, EngineMove};
+    use crate::game::GameOutcome::{Draw, Win};
+    use crate::game::GameStatus::Over;
+    use crate::game::{DrawReason, Game, SmallBasicGame, WinReason};
+    use crate::pieces::Side::{Attacker, Defender};
+    use crate::preset::{boards, rules};
+
+    /// Plays the first valid play, always reporting the same evaluation.
+    struct FixedEval(Option<f64>);
+
+    impl Engine<SmallBasicBoardState> for FixedEval {
+        fn choose_play(&mut self, game: &Game<SmallBasicBoardState>) -> EngineMove {
+            EngineMove { play: game.valid_plays()[0].play, eval: self.0 }
+        }
+    }
+
+    #[test]
+    fn test_adjudicator() {
+        let mut adjudicator = Adjudicator::new(AdjudicationRules {
+            win_threshold: Some(3.0),
+            win_moves: 2,
+            ..Default::default()
+        });
+        assert_eq!(adjudicator.observe(Some(-4.0), 1), None);
+        assert_eq!(adjudicator.observe(Some(-3.5), 2), None);
+        assert_eq!(adjudicator.observe(Some(5.0), 3), None);
+        assert_eq!(adjudicator.observe(Some(-3.0), 4), None);
+        assert_eq!(adjudicator.observe(None, 5), None);
+        for plays in 6..9 {
+            assert_eq!(adjudicator.observe(Some(-3.0), plays), None);
+        }
+        assert_eq!(adjudicator.observe(Some(-3.0), 9), Some(Win(WinReason::Adjudicated, Defender)));
+    }
+
+    #[test]
+    fn test_run_game() {
+        let mut game = SmallBasicGame::new(rules::BRANDUBH, boards::BRANDUBH).unwrap();
+        let result = run_game(&mut game, &mut FixedEval(Some(2.0)), &mut FixedEval(Some(1.5)), 100, Some(AdjudicationRules {
+            win_threshold: Some(1.0),
+            win_moves: 2,
+            ..Default::default()
+        })).unwrap();
+        assert_eq!(result.outcome, Some(Win(WinReason::Adjudicated, Attacker)));
+        assert!(result.adjudicated);
+        assert_eq!(result.evals, vec![Some(2.0), Some(1.5), Some(2.0), Some(1.5)]);
+        assert_eq!(game.state.status, Over(Win(WinReason::Adjudicated, Attacker)));
+
+        let mut game = SmallBasicGame::new(rules::BRANDUBH, boards::BRANDUBH).unwrap();
+        let result = run_game(&mut game, &mut FixedEval(Some(0.0)), &mut FixedEval(Some(0.1)), 100, Some(AdjudicationRules {
+            draw_threshold: Some(0.2),
+            draw_moves: 1,
+            draw_min_plays: 3,
+            ..Default::default()
+        })).unwrap();
+        assert_eq!(result.outcome, Some(Draw(DrawReason::Adjudicated)));
+        assert_eq!(result.evals.len(), 3);
+
+        let mut game = SmallBasicGame::new(rules::BRANDUBH, boards::BRANDUBH).unwrap();
+        let result = run_game(&mut game, &mut FixedEval(None), &mut FixedEval(Some(9.0)), 6, Some(AdjudicationRules {
+            win_threshold: Some(1.0),
+            win_moves: 1,
+            ..Default::default()
+        })).unwrap();
+        assert!(!result.adjudicated);
+        assert_eq!(result.evals.len(), 6);
+    }
+}
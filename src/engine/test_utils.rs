@@ -0,0 +1,23 @@
+use crate::analysis::playout::PlayoutPolicy;
+use crate::board::state::BoardState;
+use crate::engine::{Engine, EngineMove};
+use crate::game::logic::GameLogic;
+use crate::game::state::GameState;
+use crate::game::Game;
+use crate::play::ValidPlay;
+
+/// Always makes the first valid play. As an engine, it evaluates each position by the number of
+/// plays made so far, so that evaluations can be traced back to the positions they were made in.
+pub(crate) struct FirstPlay;
+
+impl<T: BoardState> Engine<T> for FirstPlay {
+    fn choose_play(&mut self, game: &Game<T>) -> EngineMove {
+        EngineMove { play: game.valid_plays()[0].play, eval: Some(game.play_history.len() as f64) }
+    }
+}
+
+impl<T: BoardState> PlayoutPolicy<T> for FirstPlay {
+    fn choose_play(&mut self, logic: &GameLogic, state: &GameState<T>) -> Option<ValidPlay> {
+        logic.valid_plays(state).first().copied()
+    }
+}
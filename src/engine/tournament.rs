@@ -0,0 +1,469 @@
+use crate::board::state::BoardState;
+use crate::engine::runner::{run_game, AdjudicationRules, EngineGameResult};
+use crate::engine::Engine;
+use crate::error::TournamentError;
+use crate::game::Game;
+use crate::game::GameOutcome::{Draw, Win};
+use crate::pieces::Side::{Attacker, Defender};
+use crate::rules::Ruleset;
+use std::collections::HashSet;
+
+/// A variant played in a [`Tournament`]: a ruleset together with a starting position.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Variant {
+    /// A name for the variant, used when reporting results.
+    pub name: String,
+    /// The rules of the variant.
+    pub rules: Ruleset,
+    /// The starting position, as a FEN-style string.
+    pub starting_board: String
+}
+
+impl Variant {
+
+    /// Create a new variant.
+    pub fn new(name: &str, rules: Ruleset, starting_board: &str) -> Self {
+        Self { name: name.to_string(), rules, starting_board: starting_board.to_string() }
+    }
+}
+
+/// How players are paired in each round of a [`Tournament`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Pairing {
+    /// Every player meets every other player once. With an odd number of players, one player sits
+    /// out each round.
+    RoundRobin,
+    /// The given number of rounds, in each of which players are paired with others on the same
+    /// (or the nearest) score whom they have not yet met. With an odd number of players, the
+    /// lowest-ranked player who has not yet had a bye sits out, scoring as if they had won every
+    /// game of the round.
+    Swiss { rounds: usize }
+}
+
+/// A game played in a [`Tournament`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TournamentGame {
+    /// The round in which the game was played (counting from 0).
+    pub round: usize,
+    /// The index of the variant played.
+    pub variant: usize,
+    /// The index of the engine which played as attacker.
+    pub attacker: usize,
+    /// The index of the engine which played as defender.
+    pub defender: usize,
+    /// The result of the game.
+    pub result: EngineGameResult
+}
+
+impl TournamentGame {
+
+    /// The number of half-points scored by the attacker and the defender. Games which were
+    /// stopped after the maximum number of plays are scored as draws.
+    pub fn half_points(&self) -> (usize, usize) {
+        match self.result.outcome {
+            Some(Win(_, Attacker)) => (2, 0),
+            Some(Win(_, Defender)) => (0, 2),
+            Some(Draw(_)) | None => (1, 1)
+        }
+    }
+}
+
+/// The number of games in a [`Tournament`] won by each side, for comparing the balance of
+/// variants.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct SideResults {
+    /// Games won by the attacker.
+    pub attacker_wins: usize,
+    /// Games won by the defender.
+    pub defender_wins: usize,
+    /// Games drawn.
+    pub draws: usize,
+    /// Games stopped after the maximum number of plays.
+    pub unfinished: usize
+}
+
+/// The scores of the players in a [`Tournament`] against each other.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Crosstable {
+    half_points: Vec<Vec<usize>>,
+    games: Vec<Vec<usize>>,
+    bye_half_points: Vec<usize>
+}
+
+impl Crosstable {
+
+    fn new(players: usize) -> Self {
+        Self {
+            half_points: vec![vec![0; players]; players],
+            games: vec![vec![0; players]; players],
+            bye_half_points: vec![0; players]
+        }
+    }
+
+    fn add_game(&mut self, game: &TournamentGame) {
+        let (a, d) = (game.attacker, game.defender);
+        let (a_hp, d_hp) = game.half_points();
+        self.half_points[a][d] += a_hp;
+        self.half_points[d][a] += d_hp;
+        self.games[a][d] += 1;
+        self.games[d][a] += 1;
+    }
+
+    /// The number of players.
+    pub fn players(&self) -> usize {
+        self.half_points.len()
+    }
+
+    /// The number of points scored by player `i` in games against player `j`.
+    pub fn score_against(&self, i: usize, j: usize) -> f64 {
+        self.half_points[i][j] as f64 / 2.0
+    }
+
+    /// The number of games played between players `i` and `j`.
+    pub fn games_against(&self, i: usize, j: usize) -> usize {
+        self.games[i][j]
+    }
+
+    /// The total number of points scored by the given player, including any awarded for byes.
+    pub fn score(&self, player: usize) -> f64 {
+        let half_points: usize = self.half_points[player].iter().sum();
+        (half_points + self.bye_half_points[player]) as f64 / 2.0
+    }
+
+    /// The players, ordered by total score (highest first). Players with equal scores are ordered
+    /// by index.
+    pub fn standings(&self) -> Vec<usize> {
+        let mut players: Vec<usize> = (0..self.players()).collect();
+        players.sort_by(|&i, &j| self.score(j).total_cmp(&self.score(i)).then(i.cmp(&j)));
+        players
+    }
+
+    /// Render the crosstable as text, using the given names for the players (which must be one
+    /// per player). Each row shows a player's score against each other player, followed by their
+    /// total score; pairs of players who did not meet are marked with `.`.
+    pub fn to_table(&self, names: &[&str]) -> String {
+        let n = self.players();
+        let num_width = n.to_string().len();
+        let name_width = names.iter().map(|s| s.chars().count()).max().unwrap_or(0);
+        let mut header = format!("{:num_width$} {:name_width$}", "", "");
+        for j in 0..n {
+            header.push_str(&format!(" {:>5}", j + 1));
+        }
+        header.push_str(" Score");
+        let mut lines = vec![header];
+        for (i, name) in names.iter().enumerate().take(n) {
+            let mut line = format!("{:>num_width$} {name:name_width$}", i + 1);
+            for j in 0..n {
+                let cell = if i == j {
+                    "-".to_string()
+                } else if self.games[i][j] == 0 {
+                    ".".to_string()
+                } else {
+                    format!("{:.1}", self.score_against(i, j))
+                };
+                line.push_str(&format!(" {cell:>5}"));
+            }
+            line.push_str(&format!(" {:>5.1}", self.score(i)));
+            lines.push(line);
+        }
+        lines.join("\n")
+    }
+}
+
+/// A tournament between a number of engines across one or more variants. Whenever two engines
+/// are paired, they play one game on each variant with each engine as attacker, so that neither
+/// benefits from any imbalance between the sides.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tournament {
+    variants: Vec<Variant>,
+    pairing: Pairing,
+    max_plays: usize,
+    adjudication: Option<AdjudicationRules>,
+    players: usize,
+    games: Vec<TournamentGame>,
+    byes: Vec<(usize, usize)>
+}
+
+/// The pairings for each round of a round robin between the given number of players, using the
+/// circle method. Each pairing is a pair of player indices; a player sitting out is paired with
+/// `None`.
+fn round_robin_rounds(players: usize) -> Vec<Vec<(usize, Option<usize>)>> {
+    if players < 2 {
+        return vec![]
+    }
+    // With an odd number of players, a dummy player (with index `players`) stands for a bye.
+    let m = players + players % 2;
+    let mut rounds = vec![];
+    for r in 0..m - 1 {
+        let mut circle = vec![0];
+        circle.extend((0..m - 1).map(|k| (r + k) % (m - 1) + 1));
+        let round = (0..m / 2).map(|i| {
+            let (a, b) = (circle[i], circle[m - 1 - i]);
+            if a == players {
+                (b, None)
+            } else if b == players {
+                (a, None)
+            } else {
+                (a, Some(b))
+            }
+        }).collect();
+        rounds.push(round);
+    }
+    rounds
+}
+
+/// Get mutable references to two different engines.
+fn pair_mut<'a, T: BoardState>(
+    engines: &'a mut [&mut dyn Engine<T>],
+    i: usize,
+    j: usize
+) -> (&'a mut dyn Engine<T>, &'a mut dyn Engine<T>) {
+    if i < j {
+        let (left, right) = engines.split_at_mut(j);
+        (&mut *left[i], &mut *right[0])
+    } else {
+        let (left, right) = engines.split_at_mut(i);
+        (&mut *right[0], &mut *left[j])
+    }
+}
+
+impl Tournament {
+
+    /// Create a new tournament over the given variants. Each game is run using [`run_game`] with
+    /// the given maximum number of plays and adjudication rules.
+    pub fn new(
+        variants: Vec<Variant>,
+        pairing: Pairing,
+        max_plays: usize,
+        adjudication: Option<AdjudicationRules>
+    ) -> Self {
+        Self { variants, pairing, max_plays, adjudication, players: 0, games: vec![], byes: vec![] }
+    }
+
+    /// The variants played in the tournament.
+    pub fn variants(&self) -> &[Variant] {
+        &self.variants
+    }
+
+    /// The games played so far, in the order they were played.
+    pub fn games(&self) -> &[TournamentGame] {
+        &self.games
+    }
+
+    /// The byes awarded so far, as `(round, player)` pairs. Byes are only recorded (and scored)
+    /// under [`Pairing::Swiss`].
+    pub fn byes(&self) -> &[(usize, usize)] {
+        &self.byes
+    }
+
+    /// Run the tournament between the given engines, which are referred to by their index in
+    /// the slice. Any results from a previous run are discarded.
+    ///
+    /// Returns an error if the starting position of any variant cannot be parsed or if an engine
+    /// makes an invalid play.
+    pub fn run<T: BoardState>(
+        &mut self,
+        engines: &mut [&mut dyn Engine<T>]
+    ) -> Result<Crosstable, TournamentError> {
+        for (index, variant) in self.variants.iter().enumerate() {
+            Game::<T>::new(variant.rules, &variant.starting_board)
+                .map_err(|error| TournamentError::BadVariant { index, error })?;
+        }
+        self.players = engines.len();
+        self.games.clear();
+        self.byes.clear();
+        match self.pairing {
+            Pairing::RoundRobin => {
+                for (round, pairs) in round_robin_rounds(self.players).into_iter().enumerate() {
+                    for (a, b) in pairs {
+                        if let Some(b) = b {
+                            self.play_pairing(engines, round, a, b)?;
+                        }
+                    }
+                }
+            },
+            Pairing::Swiss { rounds } => {
+                let mut met = HashSet::new();
+                for round in 0..rounds {
+                    let pairs = self.swiss_pairs(&met);
+                    for (a, b) in pairs {
+                        met.insert((a.min(b), a.max(b)));
+                        self.play_pairing(engines, round, a, b)?;
+                    }
+                }
+            }
+        }
+        Ok(self.crosstable())
+    }
+
+    /// Pair players for the next Swiss round, recording a bye if required.
+    fn swiss_pairs(&mut self, met: &HashSet<(usize, usize)>) -> Vec<(usize, usize)> {
+        let round = self.games.last().map_or(0, |g| g.round + 1)
+            .max(self.byes.last().map_or(0, |&(r, _)| r + 1));
+        let mut unpaired = self.crosstable().standings();
+        if unpaired.len() % 2 == 1 {
+            let bye = unpaired.iter()
+                .rposition(|&p| !self.byes.iter().any(|&(_, q)| q == p))
+                .unwrap_or(unpaired.len() - 1);
+            self.byes.push((round, unpaired.remove(bye)));
+        }
+        let mut pairs = vec![];
+        while !unpaired.is_empty() {
+            let a = unpaired.remove(0);
+            let b = unpaired.iter()
+                .position(|&b| !met.contains(&(a.min(b), a.max(b))))
+                .unwrap_or(0);
+            pairs.push((a, unpaired.remove(b)));
+        }
+        pairs
+    }
+
+    /// Play one game on each variant with each of the two engines as attacker.
+    fn play_pairing<T: BoardState>(
+        &mut self,
+        engines: &mut [&mut dyn Engine<T>],
+        round: usize,
+        a: usize,
+        b: usize
+    ) -> Result<(), TournamentError> {
+        for variant in 0..self.variants.len() {
+            for (attacker, defender) in [(a, b), (b, a)] {
+                let v = &self.variants[variant];
+                let mut game = Game::<T>::new(v.rules, &v.starting_board)
+                    .map_err(|error| TournamentError::BadVariant { index: variant, error })?;
+                let (att_engine, def_engine) = pair_mut(engines, attacker, defender);
+                let result = run_game(&mut game, att_engine, def_engine, self.max_plays, self.adjudication)
+                    .map_err(|reason| TournamentError::InvalidPlay { game: self.games.len(), reason })?;
+                self.games.push(TournamentGame { round, variant, attacker, defender, result });
+            }
+        }
+        Ok(())
+    }
+
+    /// The crosstable of all games played so far.
+    pub fn crosstable(&self) -> Crosstable {
+        let mut table = Crosstable::new(self.players);
+        for game in &self.games {
+            table.add_game(game);
+        }
+        // A bye scores as a win in every game the player would have played that round.
+        let bye_half_points = 2 * 2 * self.variants.len();
+        for &(_, player) in &self.byes {
+            table.bye_half_points[player] += bye_half_points;
+        }
+        table
+    }
+
+    /// The crosstable of the games played so far on the given variant only. Byes are not counted.
+    pub fn variant_crosstable(&self, variant: usize) -> Crosstable {
+        let mut table = Crosstable::new(self.players);
+        for game in self.games.iter().filter(|g| g.variant == variant) {
+            table.add_game(game);
+        }
+        table
+    }
+
+    /// The number of games on the given variant won by each side.
+    pub fn side_results(&self, variant: usize) -> SideResults {
+        let mut results = SideResults::default();
+        for game in self.games.iter().filter(|g| g.variant == variant) {
+            match game.result.outcome {
+                Some(Win(_, Attacker)) => results.attacker_wins += 1,
+                Some(Win(_, Defender)) => results.defender_wins += 1,
+                Some(Draw(_)) => results.draws += 1,
+                None => results.unfinished += 1
+            }
+        }
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::state::SmallBasicBoardState;
+    use crate::engine::tournament::{round_robin_rounds, Pairing, SideResults, Tournament, Variant};
+    use crate::engine::runner::AdjudicationRules;
+    use crate::engine::{Engine, EngineMove};
+    use crate::error::TournamentError;
+    use crate::game::Game;
+    use crate::preset::{boards, rules};
+    use crate::rules::Ruleset;
+    use std::collections::HashSet;
+
+    /// Plays the first valid play, always reporting an evaluation favouring the attacker.
+    struct AttackerWins;
+
+    impl Engine<SmallBasicBoardState> for AttackerWins {
+        fn choose_play(&mut self, game: &Game<SmallBasicBoardState>) -> EngineMove {
+            EngineMove { play: game.valid_plays()[0].play, eval: Some(1.0) }
+        }
+    }
+
+    #[test]
+    fn test_round_robin_rounds() {
+        for players in 2..8 {
+            let rounds = round_robin_rounds(players);
+            let mut met = HashSet::new();
+            for round in &rounds {
+                let mut seen = HashSet::new();
+                for &(a, b) in round {
+                    assert!(seen.insert(a));
+                    if let Some(b) = b {
+                        assert!(seen.insert(b));
+                        assert!(met.insert((a.min(b), a.max(b))));
+                    }
+                }
+                assert_eq!(seen.len(), players);
+            }
+            assert_eq!(met.len(), players * (players - 1) / 2);
+        }
+    }
+
+    #[test]
+    fn test_tournament() {
+        let variants = vec![
+            Variant::new("Brandubh", rules::BRANDUBH, boards::BRANDUBH),
+            Variant::new("Brandubh (edge escape)", Ruleset { edge_escape: true, ..rules::BRANDUBH }, boards::BRANDUBH)
+        ];
+        let adjudication = Some(AdjudicationRules {
+            win_threshold: Some(1.0),
+            win_moves: 1,
+            ..Default::default()
+        });
+        let (mut e0, mut e1, mut e2) = (AttackerWins, AttackerWins, AttackerWins);
+
+        // Every game is adjudicated as a win for the attacker, so each pairing is shared.
+        let mut tournament = Tournament::new(variants.clone(), Pairing::RoundRobin, 50, adjudication);
+        let table = tournament.run::<SmallBasicBoardState>(&mut [&mut e0, &mut e1, &mut e2]).unwrap();
+        assert_eq!(tournament.games().len(), 3 * 2 * 2);
+        assert_eq!(table.games_against(0, 1), 4);
+        assert_eq!(table.score_against(0, 1), 2.0);
+        assert_eq!(table.score(2), 4.0);
+        assert_eq!(tournament.side_results(1), SideResults { attacker_wins: 6, ..Default::default() });
+        assert_eq!(
+            table.to_table(&["one", "two", "three"]),
+            [
+                "            1     2     3 Score",
+                "1 one       -   2.0   2.0   4.0",
+                "2 two     2.0     -   2.0   4.0",
+                "3 three   2.0   2.0     -   4.0"
+            ].join("\n")
+        );
+
+        let mut tournament = Tournament::new(variants.clone(), Pairing::Swiss { rounds: 3 }, 50, adjudication);
+        let table = tournament.run::<SmallBasicBoardState>(&mut [&mut e0, &mut e1, &mut e2]).unwrap();
+        let bye_players: HashSet<usize> = tournament.byes().iter().map(|&(_, p)| p).collect();
+        assert_eq!(bye_players.len(), 3);
+        assert_eq!(tournament.games().len(), 3 * 4);
+        assert_eq!(table.standings(), vec![0, 1, 2]);
+        assert_eq!(table.score(0), 8.0);
+        assert_eq!(tournament.variant_crosstable(0).score(0), 2.0);
+
+        let bad = vec![Variant::new("Bad", rules::BRANDUBH, "not a board")];
+        let mut tournament = Tournament::new(bad, Pairing::RoundRobin, 50, None);
+        assert!(matches!(
+            tournament.run::<SmallBasicBoardState>(&mut [&mut e0, &mut e1]),
+            Err(TournamentError::BadVariant { index: 0, .. })
+        ));
+    }
+}
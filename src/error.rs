@@ -1,5 +1,6 @@
 use std::num::ParseIntError;
 use crate::error::ParseError::BadInt;
+use crate::pieces::PieceType;
 
 /// Errors that may be encountered when parsing a string.
 #[derive(Debug, Eq, PartialEq)]
@@ -19,6 +20,12 @@ pub enum ParseError {
     BadInt(ParseIntError),
     /// Tried to parse a string which represents an invalid [`Play`].
     BadPlay(PlayError),
+    /// Tried to parse data written in a newer version of a format than this version of the crate
+    /// supports. The given `u32` is the version found.
+    UnsupportedVersion(u32),
+    /// The input was parsed successfully, but the [`Ruleset`](crate::rules::Ruleset) it was to be
+    /// used with is invalid. This is a problem with the rules, not with the input text.
+    BadRules(RulesError),
     /// A generic error type where the given string could not be parsed for some reason.
     BadString(String)
     
@@ -30,10 +37,33 @@ impl From<ParseIntError> for ParseError {
     }
 }
 
+impl From<RulesError> for ParseError {
+    fn from(value: RulesError) -> Self {
+        ParseError::BadRules(value)
+    }
+}
+
+/// Errors that may be encountered when validating a [`Ruleset`](crate::rules::Ruleset) against the
+/// board state it is to be used with.
+#[derive(Debug, Eq, PartialEq)]
+pub enum RulesError {
+    /// The rules promote pieces to the given type, which cannot be stored by the board state being
+    /// used.
+    UnsupportedPromotion(PieceType),
+    /// The rules promote pieces to a king, which is never allowed.
+    PromotionToKing
+}
+
 /// Errors that may be encountered when constructing a [`Play`].
 #[derive(Debug, Eq, PartialEq)]
 pub enum PlayError {
-    DisjointTiles
+    DisjointTiles,
+    /// The play cannot be represented in the compact binary encoding used by
+    /// [`Play::encode`](crate::play::Play::encode), as it is on a board larger than 19x19.
+    NotEncodable,
+    /// The given `u16` is not a valid compact encoding of a play (see
+    /// [`Play::decode`](crate::play::Play::decode)).
+    BadEncoding(u16)
 }
 
 /// Errors relating to the board.
@@ -45,6 +75,112 @@ pub enum BoardError {
     NoPiece
 }
 
+/// Errors that may be encountered when generating a random starting position.
+#[derive(Debug, Eq, PartialEq)]
+pub enum SetupError {
+    /// The board has an even side length, so has no central tile.
+    BadBoardSize,
+    /// The number of pieces requested is incompatible with the requested symmetry.
+    BadPieceCount,
+    /// There are not enough suitable tiles to place the requested pieces.
+    NotEnoughTiles
+}
+
+/// Errors that may be encountered when interacting with a
+/// [`GameManager`](crate::game::manager::GameManager).
+#[derive(Debug, Eq, PartialEq)]
+pub enum ManagerError {
+    /// There is no game with the given id.
+    NoSuchGame,
+    /// The requested play is invalid. This variant wraps the [`PlayInvalid`] describing why.
+    InvalidPlay(PlayInvalid)
+}
+
+impl From<PlayInvalid> for ManagerError {
+    fn from(value: PlayInvalid) -> Self {
+        ManagerError::InvalidPlay(value)
+    }
+}
+
+/// Errors that may be encountered when verifying or replaying a
+/// [`GameRecord`](crate::record::GameRecord).
+#[derive(Debug, Eq, PartialEq)]
+pub enum RecordError {
+    /// The record does not contain a checksum.
+    MissingChecksum,
+    /// The checksum stored in the record does not match the checksum calculated from its contents,
+    /// indicating that the record has been truncated or altered.
+    ChecksumMismatch { expected: u64, actual: u64 },
+    /// The starting position could not be parsed. This variant wraps the [`ParseError`]
+    /// describing why.
+    BadPosition(ParseError),
+    /// The play at the given index in the record is invalid.
+    InvalidPlay { index: usize, reason: PlayInvalid }
+}
+
+/// Errors that may be encountered when reading an archive of game records using an
+/// [`ArchiveReader`](crate::record::ArchiveReader). Each error relates to a single record, and
+/// reading continues with the next record (except after an I/O error).
+#[derive(Debug, Eq, PartialEq)]
+pub enum ArchiveError {
+    /// Reading from the underlying source failed. This variant wraps the kind of I/O error.
+    Io(std::io::ErrorKind),
+    /// The record with the given index, starting on the given line (counting from 1), could not
+    /// be parsed.
+    BadRecord { index: usize, line: usize, error: ParseError },
+    /// The record with the given index could not be replayed.
+    BadGame { index: usize, error: RecordError }
+}
+
+/// Errors that may be encountered when running a
+/// [`Tournament`](crate::engine::tournament::Tournament).
+#[derive(Debug, Eq, PartialEq)]
+pub enum TournamentError {
+    /// The starting position of the variant with the given index could not be parsed.
+    BadVariant { index: usize, error: ParseError },
+    /// An engine made an invalid play in the game with the given index.
+    InvalidPlay { game: usize, reason: PlayInvalid }
+}
+
+/// Errors that may be encountered when checking that a [`Puzzle`](crate::puzzle::Puzzle) is
+/// well formed.
+#[derive(Debug, Eq, PartialEq)]
+pub enum PuzzleError {
+    /// The position could not be parsed. This variant wraps the [`ParseError`] describing why.
+    BadPosition(ParseError),
+    /// The puzzle has no solution lines.
+    NoSolution,
+    /// The play at the given index of the given solution line is invalid.
+    InvalidPlay { line: usize, index: usize, reason: PlayInvalid },
+    /// The given solution line does not achieve the puzzle's objective.
+    ObjectiveNotMet { line: usize }
+}
+
+/// Errors that may be encountered when creating a [`Match`](crate::game::series::Match).
+#[derive(Debug, Eq, PartialEq)]
+pub enum MatchError {
+    /// The match was created with no games.
+    NoGames,
+    /// The starting position could not be parsed. This variant wraps the [`ParseError`]
+    /// describing why.
+    BadPosition(ParseError)
+}
+
+/// Errors that may be encountered when building a game using a
+/// [`GameBuilder`](crate::game::builder::GameBuilder).
+#[derive(Debug, Eq, PartialEq)]
+pub enum BuildError {
+    /// No ruleset was specified.
+    MissingRules,
+    /// No starting position was specified.
+    MissingBoard,
+    /// The starting position could not be parsed. This variant wraps the [`ParseError`]
+    /// describing why.
+    BadPosition(ParseError),
+    /// The pre-played play at the given index is invalid.
+    InvalidPlay { index: usize, reason: PlayInvalid }
+}
+
 /// Different ways a [`Play`] can be invalid.
 #[derive(Debug, Eq, PartialEq)]
 pub enum PlayInvalid {
@@ -66,6 +202,10 @@ pub enum PlayInvalid {
     MoveOntoBlockedTile,
     /// The move is further than this piece is permitted to move in one go.
     TooFar,
+    /// The move is not permitted as the first play of the game.
+    FirstPlayRestricted,
+    /// The players may not swap sides at this point in the game.
+    SwapNotPermitted,
     /// Game is already over.
     GameOver
 }
\ No newline at end of file
@@ -0,0 +1,161 @@
+use crate::board::state::BoardState;
+use crate::error::BuildError;
+use crate::error::BuildError::{BadPosition, InvalidPlay, MissingBoard, MissingRules};
+use crate::game::clock::{Clock, TimeControl};
+use crate::game::manager::ManagedGame;
+use crate::game::Game;
+use crate::pieces::Side;
+use crate::play::Play;
+use crate::rules::Ruleset;
+use std::marker::PhantomData;
+
+/// A builder for configuring a new [`Game`], created using [`Game::builder`]. Nothing is validated
+/// until [`Self::build`] (or [`Self::build_timed`]) is called.
+///
+/// ```
+/// use hnefatafl::game::SmallBasicGame;
+/// use hnefatafl::play::Play;
+/// use hnefatafl::preset::{boards, rules};
+/// use std::str::FromStr;
+///
+/// let game = SmallBasicGame::builder()
+///     .preset(rules::BRANDUBH, boards::BRANDUBH)
+///     .play(Play::from_str("d1-c1").unwrap())
+///     .build()
+///     .unwrap();
+/// assert_eq!(game.play_history.len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GameBuilder<T: BoardState> {
+    rules: Option<Ruleset>,
+    board: Option<String>,
+    starting_side: Option<Side>,
+    time_control: Option<TimeControl>,
+    plays: Vec<Play>,
+    _board_state: PhantomData<T>
+}
+
+impl<T: BoardState> Default for GameBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: BoardState> GameBuilder<T> {
+
+    /// Create a new builder with nothing configured.
+    pub fn new() -> Self {
+        Self {
+            rules: None,
+            board: None,
+            starting_side: None,
+            time_control: None,
+            plays: vec![],
+            _board_state: PhantomData
+        }
+    }
+
+    /// Set the rules of the game.
+    pub fn rules(mut self, rules: Ruleset) -> Self {
+        self.rules = Some(rules);
+        self
+    }
+
+    /// Set the starting position of the game, in FEN format.
+    pub fn board(mut self, board: &str) -> Self {
+        self.board = Some(board.to_string());
+        self
+    }
+
+    /// Set both the rules and the starting position, such as one of the pairs provided in the
+    /// [`crate::preset`] module.
+    pub fn preset(self, rules: Ruleset, board: &str) -> Self {
+        self.rules(rules).board(board)
+    }
+
+    /// Set the side that plays first, overriding the starting side specified by the rules.
+    pub fn starting_side(mut self, side: Side) -> Self {
+        self.starting_side = Some(side);
+        self
+    }
+
+    /// Set the time control for the game. This is only used by [`Self::build_timed`].
+    pub fn time_control(mut self, time_control: TimeControl) -> Self {
+        self.time_control = Some(time_control);
+        self
+    }
+
+    /// Add a play to be made once the game has been created.
+    pub fn play(mut self, play: Play) -> Self {
+        self.plays.push(play);
+        self
+    }
+
+    /// Add a number of plays to be made (in order) once the game has been created.
+    pub fn plays(mut self, plays: impl IntoIterator<Item=Play>) -> Self {
+        self.plays.extend(plays);
+        self
+    }
+
+    /// Create the game, checking that the rules and starting position have been specified and are
+    /// valid, and making any plays that have been added.
+    pub fn build(&self) -> Result<Game<T>, BuildError> {
+        let mut rules = self.rules.ok_or(MissingRules)?;
+        if let Some(side) = self.starting_side {
+            rules.starting_side = side;
+        }
+        let board = self.board.as_ref().ok_or(MissingBoard)?;
+        let mut game = Game::new(rules, board).map_err(BadPosition)?;
+        for (index, play) in self.plays.iter().enumerate() {
+            game.do_play(*play).map_err(|reason| InvalidPlay { index, reason })?;
+        }
+        Ok(game)
+    }
+
+    /// Create the game as with [`Self::build`], together with a clock set according to the time
+    /// control (if one has been specified).
+    pub fn build_timed(&self) -> Result<ManagedGame<T>, BuildError> {
+        Ok(ManagedGame { game: self.build()?, clock: self.time_control.map(Clock::new) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::BuildError::{BadPosition, InvalidPlay, MissingBoard, MissingRules};
+    use crate::error::PlayInvalid::WrongPlayer;
+    use crate::game::clock::TimeControl;
+    use crate::game::SmallBasicGame;
+    use crate::pieces::Side::{Attacker, Defender};
+    use crate::play::Play;
+    use crate::preset::{boards, rules};
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    #[test]
+    fn test_game_builder() {
+        assert_eq!(SmallBasicGame::builder().board(boards::BRANDUBH).build().err(), Some(MissingRules));
+        assert_eq!(SmallBasicGame::builder().rules(rules::BRANDUBH).build().err(), Some(MissingBoard));
+        assert!(matches!(
+            SmallBasicGame::builder().preset(rules::BRANDUBH, "7/7/7/3X3/7/7/7").build(),
+            Err(BadPosition(_))
+        ));
+
+        let builder = SmallBasicGame::builder()
+            .preset(rules::BRANDUBH, boards::BRANDUBH)
+            .starting_side(Defender)
+            .play(Play::from_str("d3-c3").unwrap());
+        let game = builder.build().unwrap();
+        assert_eq!(game.logic.rules.starting_side, Defender);
+        assert_eq!(game.state.side_to_play, Attacker);
+        assert!(builder.build_timed().unwrap().clock.is_none());
+
+        let tc = TimeControl::new(Duration::from_secs(60), Duration::ZERO);
+        let timed = builder.clone().time_control(tc).build_timed().unwrap();
+        assert_eq!(timed.clock.unwrap().remaining(Defender), Duration::from_secs(60));
+
+        assert_eq!(
+            builder.plays([Play::from_str("c3-c2").unwrap()]).build().err(),
+            Some(InvalidPlay { index: 1, reason: WrongPlayer })
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use crate::board::state::BoardState;
+use crate::game::logic::GameLogic;
+use crate::game::state::GameState;
+use crate::game::GameStatus::Ongoing;
+use crate::pieces::Side::{Attacker, Defender};
+use crate::play::{PlayRecord, ValidPlay};
+use crate::tiles::Tile;
+use std::collections::HashMap;
+
+/// A cache of the valid plays available to each piece on the board, for both sides.
+///
+/// Whether a piece can make a given play depends only on the occupancy of the row and column it
+/// is on (as well as on the piece itself and the fixed features of the board). So after a play,
+/// the cache only recomputes the plays of pieces which share a row or column with a tile whose
+/// occupancy has changed, ie, the tiles the moving piece moved from and to and the tiles of any
+/// captured pieces. The cache is rebuilt from scratch where that is not true, ie, where the
+/// rules restrict the first play of the game, or when the game has ended.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlayCache {
+    plays: HashMap<Tile, Vec<ValidPlay>>
+}
+
+impl PlayCache {
+
+    /// Create a new cache for the given position.
+    pub fn new<T: BoardState>(logic: &GameLogic, state: &GameState<T>) -> Self {
+        let mut cache = Self::default();
+        cache.rebuild(logic, state);
+        cache
+    }
+
+    /// Recompute the plays of the piece at the given tile (or remove the tile from the cache if it
+    /// is empty).
+    fn compute<T: BoardState>(&mut self, logic: &GameLogic, state: &GameState<T>, tile: Tile) {
+        match logic.iter_plays(tile, state) {
+            Ok(iter) => { self.plays.insert(tile, iter.collect()); },
+            Err(_) => { self.plays.remove(&tile); }
+        }
+    }
+
+    /// Recompute the entire cache for the given position.
+    pub fn rebuild<T: BoardState>(&mut self, logic: &GameLogic, state: &GameState<T>) {
+        self.plays.clear();
+        for side in [Attacker, Defender] {
+            for tile in state.board.iter_occupied(side) {
+                self.compute(logic, state, tile);
+            }
+        }
+    }
+
+    /// Update the cache following the given play, which led from the position `before` to the
+    /// position `after`.
+    pub fn update<T: BoardState>(
+        &mut self,
+        logic: &GameLogic,
+        before: &GameState<T>,
+        record: &PlayRecord,
+        after: &GameState<T>
+    ) {
+        if (before.turn == 0 && logic.rules.first_play.is_some())
+            || before.status != Ongoing
+            || after.status != Ongoing {
+            self.rebuild(logic, after);
+            return
+        }
+        let mut changed = vec![record.play.from, record.play.to()];
+        changed.extend(record.effects.captures.iter().map(|c| c.tile));
+        for tile in &changed {
+            self.plays.remove(tile);
+        }
+        let affected: Vec<Tile> = self.plays.keys()
+            .copied()
+            .filter(|t| changed.iter().any(|c| c.row == t.row || c.col == t.col))
+            .collect();
+        for tile in affected.into_iter().chain([record.play.to()]) {
+            self.compute(logic, after, tile);
+        }
+    }
+
+    /// The valid plays of the piece at the given tile (which are empty if there is no piece).
+    pub fn plays_from(&self, tile: Tile) -> &[ValidPlay] {
+        self.plays.get(&tile).map_or(&[], Vec::as_slice)
+    }
+
+    /// All valid plays for the side to play in the given position, which must be the position for
+    /// which the cache was last built or updated. Plays are returned in the same order as by
+    /// [`GameLogic::valid_plays`].
+    pub fn valid_plays<T: BoardState>(&self, state: &GameState<T>) -> Vec<ValidPlay> {
+        state.board.iter_occupied(state.side_to_play)
+            .flat_map(|t| self.plays_from(t).iter().copied())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::state::{BoardState, MediumBasicBoardState, SmallBasicBoardState};
+    use crate::game::cache::PlayCache;
+    use crate::game::logic::GameLogic;
+    use crate::game::state::GameState;
+    use crate::game::GameStatus::Ongoing;
+    use crate::game::SmallBasicGame;
+    use crate::play::Play;
+    use crate::preset::{boards, rules};
+    use crate::rules::Ruleset;
+    use crate::utils::{Rng, SplitMix64};
+    use std::str::FromStr;
+
+    fn check_cache<T: BoardState>(rules: Ruleset, board: &str, seed: u64) {
+        let logic = GameLogic::new(rules, T::from_fen(board).unwrap().side_len());
+        let mut state: GameState<T> = GameState::new(board, rules.starting_side).unwrap();
+        let mut cache = PlayCache::new(&logic, &state);
+        let mut rng = SplitMix64::new(seed);
+        for _ in 0..300 {
+            let expected = logic.valid_plays(&state);
+            assert_eq!(cache.valid_plays(&state), expected);
+            if state.status != Ongoing {
+                break
+            }
+            let play = *rng.choose(&expected).unwrap();
+            let (after, record) = logic.do_valid_play(play, state).into();
+            cache.update(&logic, &state, &record, &after);
+            state = after;
+        }
+    }
+
+    #[test]
+    fn test_play_cache() {
+        for seed in 0..5 {
+            check_cache::<SmallBasicBoardState>(rules::BRANDUBH, boards::BRANDUBH, seed);
+            check_cache::<MediumBasicBoardState>(rules::COPENHAGEN, boards::COPENHAGEN, seed);
+            check_cache::<MediumBasicBoardState>(rules::ASHTON_TABLUT, boards::ASHTON_TABLUT, seed);
+        }
+    }
+
+    #[test]
+    fn test_game_play_cache() {
+        let mut game = SmallBasicGame::new(rules::BRANDUBH, boards::BRANDUBH).unwrap();
+        game.enable_play_cache();
+        game.do_play(Play::from_str("d1-c1").unwrap()).unwrap();
+        assert_eq!(game.valid_plays(), game.logic.valid_plays(&game.state));
+        game.undo_last_play();
+        assert_eq!(game.valid_plays(), game.logic.valid_plays(&game.state));
+    }
+}
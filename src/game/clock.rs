@@ -0,0 +1,212 @@
+use crate::error::ParseError;
+use crate::error::ParseError::BadString;
+use crate::pieces::Side;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// The time settings for one side: the side starts with a certain amount of time, and has a fixed
+/// amount of time added to its clock after each of its plays.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SideTimeControl {
+    /// The time the side has at the start of the game.
+    pub initial: Duration,
+    /// The time added to the side's clock after it makes a play.
+    pub increment: Duration
+}
+
+/// Settings for timing a game. The settings may differ between the sides, for example to give
+/// time odds.
+///
+/// As text, the settings for a side are written as `initial+increment` (in seconds), for example
+/// `300+5`. If the sides' settings differ, the attacker's settings are written first, separated
+/// from the defender's by a `/`, for example `300+5/600+5`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TimeControl {
+    /// The time settings for the attacker.
+    pub attacker: SideTimeControl,
+    /// The time settings for the defender.
+    pub defender: SideTimeControl
+}
+
+impl TimeControl {
+
+    /// Create a time control in which both sides have the same initial time and increment.
+    pub fn new(initial: Duration, increment: Duration) -> Self {
+        let settings = SideTimeControl { initial, increment };
+        Self { attacker: settings, defender: settings }
+    }
+
+    /// Create a time control in which each side has the given settings.
+    pub fn asymmetric(attacker: SideTimeControl, defender: SideTimeControl) -> Self {
+        Self { attacker, defender }
+    }
+
+    /// The time settings for the given side.
+    pub fn for_side(&self, side: Side) -> SideTimeControl {
+        match side {
+            Side::Attacker => self.attacker,
+            Side::Defender => self.defender
+        }
+    }
+
+    /// Whether both sides have the same settings.
+    pub fn is_symmetric(&self) -> bool {
+        self.attacker == self.defender
+    }
+}
+
+/// Write a duration as a number of seconds, to millisecond precision.
+fn fmt_secs(d: Duration, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match d.subsec_millis() {
+        0 => write!(f, "{}", d.as_secs()),
+        millis => write!(f, "{}.{millis:03}", d.as_secs())
+    }
+}
+
+/// Parse a (possibly fractional) number of seconds as a duration.
+fn parse_secs(s: &str) -> Result<Duration, ParseError> {
+    s.parse::<f64>().ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .ok_or(BadString(s.to_string()))
+}
+
+impl Display for SideTimeControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fmt_secs(self.initial, f)?;
+        write!(f, "+")?;
+        fmt_secs(self.increment, f)
+    }
+}
+
+impl FromStr for SideTimeControl {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (initial, increment) = s.split_once('+').ok_or(BadString(s.to_string()))?;
+        Ok(Self { initial: parse_secs(initial)?, increment: parse_secs(increment)? })
+    }
+}
+
+impl Display for TimeControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_symmetric() {
+            write!(f, "{}", self.attacker)
+        } else {
+            write!(f, "{}/{}", self.attacker, self.defender)
+        }
+    }
+}
+
+impl FromStr for TimeControl {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('/') {
+            Some((attacker, defender)) => Ok(Self::asymmetric(attacker.parse()?, defender.parse()?)),
+            None => {
+                let settings: SideTimeControl = s.parse()?;
+                Ok(Self::new(settings.initial, settings.increment))
+            }
+        }
+    }
+}
+
+/// A clock keeping track of the time each side has remaining.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Clock {
+    /// The time control according to which the clock runs.
+    pub time_control: TimeControl,
+    attacker_remaining: Duration,
+    defender_remaining: Duration
+}
+
+impl Clock {
+
+    /// Create a new clock, with each side having the initial time specified by the time control.
+    pub fn new(time_control: TimeControl) -> Self {
+        Self {
+            time_control,
+            attacker_remaining: time_control.attacker.initial,
+            defender_remaining: time_control.defender.initial
+        }
+    }
+
+    /// The time the given side has remaining.
+    pub fn remaining(&self, side: Side) -> Duration {
+        match side {
+            Side::Attacker => self.attacker_remaining,
+            Side::Defender => self.defender_remaining
+        }
+    }
+
+    fn remaining_mut(&mut self, side: Side) -> &mut Duration {
+        match side {
+            Side::Attacker => &mut self.attacker_remaining,
+            Side::Defender => &mut self.defender_remaining
+        }
+    }
+
+    /// Deduct the given amount of time from the given side's clock. Returns `true` if the side has
+    /// run out of time.
+    pub fn deduct(&mut self, side: Side, elapsed: Duration) -> bool {
+        let remaining = self.remaining_mut(side);
+        *remaining = remaining.saturating_sub(elapsed);
+        remaining.is_zero()
+    }
+
+    /// Add the side's increment to the given side's clock, following a play by that side.
+    pub fn add_increment(&mut self, side: Side) {
+        let increment = self.time_control.for_side(side).increment;
+        *self.remaining_mut(side) += increment;
+    }
+
+    /// Whether the given side has run out of time.
+    pub fn flagged(&self, side: Side) -> bool {
+        self.remaining(side).is_zero()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::clock::{Clock, SideTimeControl, TimeControl};
+    use crate::pieces::Side::{Attacker, Defender};
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    #[test]
+    fn test_clock() {
+        let mut clock = Clock::new(TimeControl::new(Duration::from_secs(10), Duration::from_secs(2)));
+        assert!(!clock.deduct(Attacker, Duration::from_secs(4)));
+        clock.add_increment(Attacker);
+        assert_eq!(clock.remaining(Attacker), Duration::from_secs(8));
+        assert_eq!(clock.remaining(Defender), Duration::from_secs(10));
+        assert!(clock.deduct(Defender, Duration::from_secs(11)));
+        assert!(clock.flagged(Defender));
+        assert!(!clock.flagged(Attacker));
+    }
+
+    #[test]
+    fn test_asymmetric_clock() {
+        let tc = TimeControl::asymmetric(
+            SideTimeControl { initial: Duration::from_secs(300), increment: Duration::from_secs(5) },
+            SideTimeControl { initial: Duration::from_secs(60), increment: Duration::from_millis(500) }
+        );
+        assert!(!tc.is_symmetric());
+        let mut clock = Clock::new(tc);
+        assert_eq!(clock.remaining(Attacker), Duration::from_secs(300));
+        assert_eq!(clock.remaining(Defender), Duration::from_secs(60));
+        clock.add_increment(Attacker);
+        clock.add_increment(Defender);
+        assert_eq!(clock.remaining(Attacker), Duration::from_secs(305));
+        assert_eq!(clock.remaining(Defender), Duration::from_millis(60_500));
+
+        assert_eq!(tc.to_string(), "300+5/60+0.500");
+        assert_eq!(TimeControl::from_str("300+5/60+0.500"), Ok(tc));
+        let symmetric = TimeControl::new(Duration::from_secs(180), Duration::from_secs(2));
+        assert_eq!(symmetric.to_string(), "180+2");
+        assert_eq!(TimeControl::from_str("180+2"), Ok(symmetric));
+        assert!(TimeControl::from_str("180").is_err());
+        assert!(TimeControl::from_str("180+x").is_err());
+    }
+}
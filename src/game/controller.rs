@@ -0,0 +1,417 @@
+use crate::analysis::model::OutcomeModel;
+use crate::board::state::BoardState;
+use crate::engine::Engine;
+use crate::error::PlayInvalid;
+use crate::game::clock::{Clock, TimeControl};
+use crate::game::events::GameUpdate;
+use crate::game::manager::ManagedGame;
+use crate::game::state::{GameState, RepetitionStatus};
+use crate::game::GameOutcome::Win;
+use crate::game::GameStatus::Ongoing;
+use crate::game::WinReason::Timeout;
+use crate::game::{Game, GameStatus};
+use crate::pieces::Side;
+use crate::pieces::Side::Attacker;
+use crate::play::{Play, PlayRecord, ValidPlay};
+use crate::tiles::{Tile, TileSet};
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+/// A single object bundling everything a user interface typically needs to run one game: the
+/// current position, legal destinations for a selected piece, validating and making plays, undo,
+/// hints, premoves, the clock and event subscription.
+///
+/// The controller does not read the system time itself. If the game is timed, the owner should
+/// call [`Self::tick`] regularly with the time elapsed since the last call.
+///
+/// ```
+/// use hnefatafl::game::controller::GameController;
+/// use hnefatafl::game::SmallBasicGame;
+/// use hnefatafl::play::Play;
+/// use hnefatafl::preset::{boards, rules};
+/// use hnefatafl::tiles::Tile;
+/// use std::str::FromStr;
+///
+/// let game = SmallBasicGame::new(rules::BRANDUBH, boards::BRANDUBH).unwrap();
+/// let mut controller = GameController::new(game, None);
+/// assert!(controller.destinations(Tile::from_str("d1").unwrap()).contains(Tile::from_str("c1").unwrap()));
+/// controller.do_play(Play::from_str("d1-c1").unwrap()).unwrap();
+/// assert!(controller.undo());
+/// ```
+pub struct GameController<T: BoardState> {
+    managed: ManagedGame<T>,
+    hint_engine: Option<Box<dyn Engine<T>>>,
+    premove: Option<Play>
+}
+
+impl<T: BoardState> GameController<T> {
+
+    /// Create a controller for the given game, with a clock if a time control is given.
+    pub fn new(game: Game<T>, time_control: Option<TimeControl>) -> Self {
+        Self::from(ManagedGame { game, clock: time_control.map(Clock::new) })
+    }
+
+    /// Use the given engine to provide hints, instead of the default.
+    pub fn set_hint_engine(&mut self, engine: Box<dyn Engine<T>>) {
+        self.hint_engine = Some(engine);
+    }
+
+    /// The underlying game.
+    pub fn game(&self) -> &Game<T> {
+        &self.managed.game
+    }
+
+    /// The current state of the game.
+    pub fn state(&self) -> &GameState<T> {
+        &self.managed.game.state
+    }
+
+    /// The side whose turn it is to play.
+    pub fn side_to_play(&self) -> Side {
+        self.managed.game.state.side_to_play
+    }
+
+    /// Whether the game is ongoing or over.
+    pub fn status(&self) -> GameStatus {
+        self.managed.game.state.status
+    }
+
+    /// The record of the last play made, if any.
+    pub fn last_play(&self) -> Option<&PlayRecord> {
+        self.managed.game.play_history.last()
+    }
+
+    /// The tiles to which the piece on the given tile can legally move. This is empty if the tile
+    /// is empty, the piece does not belong to the side to play or the game is over.
+    pub fn destinations(&self, tile: Tile) -> TileSet {
+        self.managed.game.destinations_from(tile)
+    }
+
+    /// Check whether the given play is valid for the side to play, without making it.
+    pub fn validate_play(&self, play: Play) -> Result<ValidPlay, PlayInvalid> {
+        if self.status() != Ongoing {
+            return Err(PlayInvalid::GameOver)
+        }
+        self.managed.game.logic.validate_play(play, self.state())
+    }
+
+    /// How the given play would affect the repetition count of the side to play, so that the
+    /// player can be warned before repeating too often. See [`Game::repetition_status`].
+    pub fn repetition_status(&self, play: Play) -> Result<Option<RepetitionStatus>, PlayInvalid> {
+        self.managed.game.repetition_status(play)
+    }
+
+    /// Make the given play. If the game is timed, the increment is added to the clock of the side
+    /// that made the play.
+    ///
+    /// If the opponent has registered a premove, it is then validated against the new position
+    /// and made immediately if valid, or discarded (with a [`GameUpdate::PremoveDiscarded`]
+    /// update) if not, or if the play ends the game. If the side that made the play must play
+    /// again (under a [`DoublePlayRule`](crate::rules::DoublePlayRule)), the premove is kept until
+    /// the opponent's turn. The returned status is the status after any premove.
+    pub fn do_play(&mut self, play: Play) -> Result<GameStatus, PlayInvalid> {
+        let side = self.side_to_play();
+        let status = self.make_play(play)?;
+        if status != Ongoing {
+            self.discard_premove();
+            return Ok(status)
+        }
+        if self.managed.game.logic.plays_again(side, self.state().turn - 1) {
+            return Ok(status)
+        }
+        match self.premove.take() {
+            Some(premove) => self.make_play(premove).or_else(|_| {
+                self.managed.game.emit(GameUpdate::PremoveDiscarded(premove));
+                Ok(status)
+            }),
+            None => Ok(status)
+        }
+    }
+
+    /// Discard the premove currently registered, if any, notifying subscribers.
+    fn discard_premove(&mut self) {
+        if let Some(premove) = self.premove.take() {
+            self.managed.game.emit(GameUpdate::PremoveDiscarded(premove));
+        }
+    }
+
+    /// Make the given play and update the clock, without considering premoves.
+    fn make_play(&mut self, play: Play) -> Result<GameStatus, PlayInvalid> {
+        let side = self.side_to_play();
+        let status = self.managed.game.do_play(play)?;
+        if let Some(clock) = &mut self.managed.clock {
+            clock.add_increment(side);
+            self.managed.game.emit(GameUpdate::ClockUpdated { side, remaining: clock.remaining(side) });
+        }
+        Ok(status)
+    }
+
+    /// Register a play to be made by the side not to play as soon as it is that side's turn,
+    /// replacing any existing premove. The play is only checked loosely now (see
+    /// [`GameLogic::validate_premove`](crate::game::logic::GameLogic::validate_premove)); it is
+    /// fully validated when the opponent's play lands.
+    pub fn set_premove(&mut self, play: Play) -> Result<(), PlayInvalid> {
+        self.managed.game.logic.validate_premove(play, self.state())?;
+        self.premove = Some(play);
+        Ok(())
+    }
+
+    /// The premove currently registered, if any.
+    pub fn premove(&self) -> Option<Play> {
+        self.premove
+    }
+
+    /// Cancel the premove currently registered, returning it.
+    pub fn cancel_premove(&mut self) -> Option<Play> {
+        self.premove.take()
+    }
+
+    /// Undo the last play, if any, sending a [`GameUpdate::Undone`] update. If sides were swapped
+    /// under the pie rule after the last play, the swap is undone along with it. Returns whether a
+    /// play was undone. The clock is not affected, and any premove is discarded.
+    pub fn undo(&mut self) -> bool {
+        self.discard_premove();
+        let n_plays = self.managed.game.play_history.len();
+        if n_plays == 0 {
+            return false
+        }
+        while self.managed.game.play_history.len() == n_plays {
+            self.managed.game.undo_last_play();
+        }
+        true
+    }
+
+    /// Suggest a play for the side to play, or `None` if the game is over or there are no valid
+    /// plays. If no hint engine has been set, this chooses the play after which the side to play
+    /// has the best chance of winning according to [`OutcomeModel::DEFAULT`], which is fast but
+    /// weak. In that case plays are compared using the model's integer [`OutcomeModel::score`],
+    /// and of equally good plays the first in the order of [`Game::valid_plays`] is chosen, so the
+    /// same hint is given on every platform.
+    pub fn hint(&mut self) -> Option<Play> {
+        if self.status() != Ongoing {
+            return None
+        }
+        if let Some(engine) = &mut self.hint_engine {
+            return Some(engine.choose_play(&self.managed.game).play)
+        }
+        let game = &self.managed.game;
+        let side = game.state.side_to_play;
+        let model = OutcomeModel::DEFAULT;
+        game.valid_plays().into_iter()
+            .map(|vp| {
+                let after = game.logic.do_valid_play(vp, game.state).new_state;
+                let score = model.score(&game.logic, &after);
+                (vp.play, if side == Attacker { score } else { -score })
+            })
+            .reduce(|best, candidate| if candidate.1 > best.1 { candidate } else { best })
+            .map(|(play, _)| play)
+    }
+
+    /// The game's clock, if it is timed.
+    pub fn clock(&self) -> Option<&Clock> {
+        self.managed.clock.as_ref()
+    }
+
+    /// Deduct the given amount of time from the clock of the side to play, if the game is timed
+    /// and ongoing. If the side runs out of time, it loses the game. Returns the status of the
+    /// game.
+    pub fn tick(&mut self, elapsed: Duration) -> GameStatus {
+        let side = self.side_to_play();
+        if let (Ongoing, Some(clock)) = (self.status(), &mut self.managed.clock) {
+            let flagged = clock.deduct(side, elapsed);
+            let remaining = clock.remaining(side);
+            self.managed.game.emit(GameUpdate::ClockUpdated { side, remaining });
+            if flagged {
+                self.discard_premove();
+                self.managed.game.end(Win(Timeout, side.other()));
+            }
+        }
+        self.status()
+    }
+
+    /// Subscribe to updates about the game, including clock updates. See [`Game::events`].
+    pub fn events(&mut self) -> Receiver<GameUpdate> {
+        self.managed.game.events()
+    }
+
+    /// Consume the controller, returning the game and its clock.
+    pub fn into_inner(self) -> ManagedGame<T> {
+        self.managed
+    }
+}
+
+impl<T: BoardState> From<ManagedGame<T>> for GameController<T> {
+    fn from(managed: ManagedGame<T>) -> Self {
+        Self { managed, hint_engine: None, premove: None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::state::SmallBasicBoardState;
+    use crate::engine::{Engine, EngineMove};
+    use crate::error::PlayInvalid::{GameOver, WrongPlayer};
+    use crate::game::clock::TimeControl;
+    use crate::game::controller::GameController;
+    use crate::game::events::GameUpdate;
+    use crate::game::GameOutcome::Win;
+    use crate::game::GameStatus::{Ongoing, Over};
+    use crate::game::WinReason::{KingEscaped, Timeout};
+    use crate::game::{Game, SmallBasicGame};
+    use crate::pieces::Side::{Attacker, Defender};
+    use crate::play::Play;
+    use crate::preset::{boards, rules};
+    use crate::rules::{DoublePlayRule, Ruleset};
+    use crate::tiles::Tile;
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    struct LastPlay;
+
+    impl Engine<SmallBasicBoardState> for LastPlay {
+        fn choose_play(&mut self, game: &Game<SmallBasicBoardState>) -> EngineMove {
+            EngineMove { play: game.valid_plays().last().unwrap().play, eval: None }
+        }
+    }
+
+    #[test]
+    fn test_game_controller() {
+        let game = SmallBasicGame::new(rules::BRANDUBH, boards::BRANDUBH).unwrap();
+        let tc = TimeControl::new(Duration::from_secs(10), Duration::from_secs(2));
+        let mut controller = GameController::new(game, Some(tc));
+        let events = controller.events();
+
+        assert_eq!(controller.destinations(Tile::from_str("d1").unwrap()).len(), 4);
+        assert!(controller.destinations(Tile::from_str("d3").unwrap()).is_empty());
+        assert_eq!(controller.validate_play(Play::from_str("d3-c3").unwrap()).err(), Some(WrongPlayer));
+        assert!(controller.validate_play(Play::from_str("d1-c1").unwrap()).is_ok());
+
+        assert_eq!(controller.tick(Duration::from_secs(3)), Ongoing);
+        assert_eq!(controller.do_play(Play::from_str("d1-c1").unwrap()), Ok(Ongoing));
+        assert_eq!(controller.clock().unwrap().remaining(Attacker), Duration::from_secs(9));
+        assert_eq!(controller.side_to_play(), Defender);
+        assert_eq!(controller.last_play().unwrap().play, Play::from_str("d1-c1").unwrap());
+        assert!(controller.undo());
+        assert!(!controller.undo());
+        assert_eq!(controller.side_to_play(), Attacker);
+
+        let hint = controller.hint().unwrap();
+        assert!(controller.validate_play(hint).is_ok());
+        controller.set_hint_engine(Box::new(LastPlay));
+        let expected = controller.game().valid_plays().last().unwrap().play;
+        assert_eq!(controller.hint(), Some(expected));
+
+        assert_eq!(controller.tick(Duration::from_secs(9)), Over(Win(Timeout, Defender)));
+        assert_eq!(controller.hint(), None);
+        assert_eq!(controller.validate_play(Play::from_str("d1-c1").unwrap()).err(), Some(GameOver));
+
+        let updates: Vec<GameUpdate> = events.try_iter().collect();
+        assert_eq!(updates[0], GameUpdate::ClockUpdated { side: Attacker, remaining: Duration::from_secs(7) });
+        assert!(matches!(updates.last(), Some(GameUpdate::GameOver(_))));
+
+        // A premove by the defender is made as soon as the attacker's play lands, unless the
+        // attacker's play makes it invalid.
+        let game = SmallBasicGame::new(rules::BRANDUBH, boards::BRANDUBH).unwrap();
+        let mut controller = GameController::new(game, None);
+        let events = controller.events();
+        assert_eq!(controller.set_premove(Play::from_str("d1-c1").unwrap()), Err(WrongPlayer));
+        assert_eq!(controller.set_premove(Play::from_str("d3-d1").unwrap()), Ok(()));
+        assert_eq!(controller.set_premove(Play::from_str("c4-c1").unwrap()), Ok(()));
+        assert_eq!(controller.premove(), Some(Play::from_str("c4-c1").unwrap()));
+        assert!(controller.do_play(Play::from_str("d3-c3").unwrap()).is_err());
+        assert_eq!(controller.do_play(Play::from_str("d1-c1").unwrap()), Ok(Ongoing));
+        assert_eq!(controller.premove(), None);
+        assert_eq!(controller.side_to_play(), Defender);
+        assert!(matches!(events.try_iter().last(), Some(GameUpdate::PremoveDiscarded(_))));
+
+        controller.set_premove(Play::from_str("c1-c2").unwrap()).unwrap();
+        assert_eq!(controller.do_play(Play::from_str("c4-c5").unwrap()), Ok(Ongoing));
+        assert_eq!(controller.side_to_play(), Defender);
+        assert_eq!(controller.game().play_history.len(), 3);
+        assert_eq!(controller.last_play().unwrap().play, Play::from_str("c1-c2").unwrap());
+
+        // Under a double play rule, the premove waits until the attacker has made both plays.
+        let rules = Ruleset { double_play: Some(DoublePlayRule { side: Attacker, turn: 0 }), ..rules::BRANDUBH };
+        let mut controller = GameController::new(SmallBasicGame::new(rules, boards::BRANDUBH).unwrap(), None);
+        controller.set_premove(Play::from_str("c4-c1").unwrap()).unwrap();
+        assert_eq!(controller.do_play(Play::from_str("d1-e1").unwrap()), Ok(Ongoing));
+        assert_eq!(controller.side_to_play(), Attacker);
+        assert_eq!(controller.premove(), Some(Play::from_str("c4-c1").unwrap()));
+        assert_eq!(controller.do_play(Play::from_str("a4-a5").unwrap()), Ok(Ongoing));
+        assert_eq!(controller.premove(), None);
+        assert_eq!(controller.last_play().unwrap().play, Play::from_str("c4-c1").unwrap());
+        assert_eq!(controller.side_to_play(), Attacker);
+
+        // The default hint takes a winning escape when one is available.
+        let game = SmallBasicGame::new(rules::BRANDUBH, "1K5/7/7/7/4t2/7/7").unwrap();
+        let mut controller = GameController::new(game, None);
+        controller.do_play(Play::from_str("e5-e4").unwrap()).unwrap();
+        let hint = controller.hint().unwrap();
+        assert_eq!(controller.do_play(hint), Ok(Over(Win(KingEscaped, Defender))));
+    }
+
+    #[test]
+    fn test_timeout_with_play_cache() {
+        let mut game = SmallBasicGame::new(rules::BRANDUBH, boards::BRANDUBH).unwrap();
+        game.enable_play_cache();
+        let tc = TimeControl::new(Duration::from_secs(10), Duration::from_secs(2));
+        let mut controller = GameController::new(game, Some(tc));
+        assert!(!controller.game().valid_plays().is_empty());
+        assert_eq!(controller.tick(Duration::from_secs(10)), Over(Win(Timeout, Defender)));
+        assert!(controller.game().valid_plays().is_empty());
+        assert!(controller.destinations(Tile::from_str("d1").unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_controller_updates() {
+        let play = |s: &str| Play::from_str(s).unwrap();
+
+        // Undoing a play discards any premove and notifies subscribers.
+        let game = SmallBasicGame::new(rules::BRANDUBH, boards::BRANDUBH).unwrap();
+        let mut controller = GameController::new(game, None);
+        let events = controller.events();
+        controller.do_play(play("d1-e1")).unwrap();
+        controller.set_premove(play("e1-f1")).unwrap();
+        assert!(controller.undo());
+        let updates: Vec<GameUpdate> = events.try_iter().collect();
+        assert_eq!(updates[updates.len() - 2..], [GameUpdate::PremoveDiscarded(play("e1-f1")), GameUpdate::Undone]);
+
+        // A premove is discarded if the opponent's play ends the game.
+        let game = SmallBasicGame::new(rules::BRANDUBH, "1K5/7/7/7/4t2/7/7").unwrap();
+        let mut controller = GameController::new(game, None);
+        let events = controller.events();
+        controller.do_play(play("e5-e4")).unwrap();
+        controller.set_premove(play("e4-e5")).unwrap();
+        assert_eq!(controller.do_play(play("b1-a1")), Ok(Over(Win(KingEscaped, Defender))));
+        assert_eq!(controller.premove(), None);
+        assert_eq!(events.try_iter().last(), Some(GameUpdate::PremoveDiscarded(play("e4-e5"))));
+
+        // A premove is discarded if the opponent runs out of time.
+        let game = SmallBasicGame::new(rules::BRANDUBH, boards::BRANDUBH).unwrap();
+        let tc = TimeControl::new(Duration::from_secs(10), Duration::from_secs(2));
+        let mut controller = GameController::new(game, Some(tc));
+        let events = controller.events();
+        controller.set_premove(play("c4-c1")).unwrap();
+        assert_eq!(controller.tick(Duration::from_secs(10)), Over(Win(Timeout, Defender)));
+        assert!(events.try_iter().any(|u| u == GameUpdate::PremoveDiscarded(play("c4-c1"))));
+    }
+
+    #[test]
+    fn test_undo_swap() {
+        let rules = Ruleset { swap_rule: true, ..rules::BRANDUBH };
+        let game = SmallBasicGame::new(rules, boards::BRANDUBH).unwrap();
+        let start = game.state.board;
+        let mut controller = GameController::new(game, None);
+        controller.do_play(Play::from_str("d1-e1").unwrap()).unwrap();
+        controller.managed.game.swap_sides().unwrap();
+
+        // The swap is undone together with the play it followed.
+        assert!(controller.undo());
+        assert!(controller.game().play_history.is_empty());
+        assert!(controller.game().history.is_empty());
+        assert!(!controller.state().sides_swapped);
+        assert_eq!(controller.state().board, start);
+        assert_eq!(controller.side_to_play(), Attacker);
+        assert!(!controller.undo());
+    }
+}
@@ -0,0 +1,94 @@
+use crate::game::{Captures, GameSummary};
+use crate::pieces::Side;
+use crate::play::{Play, PlayRecord};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::time::Duration;
+
+/// An update about a single game, sent to receivers created by
+/// [`Game::events`](crate::game::Game::events).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameUpdate {
+    /// A play has been made.
+    PlayApplied(PlayRecord),
+    /// Pieces have been captured by the last play.
+    PiecesCaptured(Captures),
+    /// The clock of the given side has been updated, and now shows the given time remaining.
+    ClockUpdated { side: Side, remaining: Duration },
+    /// The game has ended.
+    GameOver(GameSummary),
+    /// The last play (or swap of sides) has been undone.
+    Undone,
+    /// A premove registered with a [`GameController`](crate::game::controller::GameController)
+    /// was discarded without being made, because it was not valid once the opponent had played,
+    /// or because the game ended or a play was undone first.
+    PremoveDiscarded(Play)
+}
+
+/// The senders for all subscribers to a game's updates. Cloning produces an empty set of
+/// subscribers, so that copies of a game (for example, made while searching for a play) do not
+/// send updates.
+#[derive(Debug, Default)]
+pub(crate) struct Subscribers(Vec<Sender<GameUpdate>>);
+
+impl Clone for Subscribers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl Subscribers {
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn subscribe(&mut self) -> Receiver<GameUpdate> {
+        let (sender, receiver) = channel();
+        self.0.push(sender);
+        receiver
+    }
+
+    /// Send an update to each subscriber, dropping any whose receiver has been dropped.
+    pub(crate) fn send(&mut self, update: GameUpdate) {
+        self.0.retain(|s| s.send(update.clone()).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::clock::TimeControl;
+    use crate::game::events::GameUpdate;
+    use crate::game::manager::GameManager;
+    use crate::game::GameOutcome::Win;
+    use crate::game::WinReason::Timeout;
+    use crate::game::SmallBasicGame;
+    use crate::pieces::Side::{Attacker, Defender};
+    use crate::play::Play;
+    use crate::preset::rules;
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    #[test]
+    fn test_game_events() {
+        let mut game = SmallBasicGame::new(rules::BRANDUBH, "7/1t5/1T5/7/7/2t4/3K3").unwrap();
+        let receiver = game.events();
+        let clone = game.clone();
+        game.do_play(Play::from_str("c6-c4").unwrap()).unwrap();
+        game.do_play(Play::from_str("d7-e7").unwrap()).unwrap();
+        game.do_play(Play::from_str("c4-b4").unwrap()).unwrap();
+        let updates: Vec<GameUpdate> = receiver.try_iter().collect();
+        assert_eq!(updates.len(), 4);
+        assert_eq!(updates[0], GameUpdate::PlayApplied(game.play_history[0].clone()));
+        assert_eq!(updates[3], GameUpdate::PiecesCaptured(game.play_history[2].effects.captures.clone()));
+        drop(clone);
+
+        let mut manager = GameManager::new();
+        let tc = TimeControl::new(Duration::from_secs(5), Duration::from_secs(1));
+        let id = manager.add_game(game, Some(tc));
+        let receiver = manager.events(id).unwrap();
+        manager.tick(Duration::from_secs(5));
+        let updates: Vec<GameUpdate> = receiver.try_iter().collect();
+        assert_eq!(updates[0], GameUpdate::ClockUpdated { side: Defender, remaining: Duration::ZERO });
+        assert!(matches!(updates[1], GameUpdate::GameOver(s) if s.outcome == Win(Timeout, Attacker)));
+    }
+}
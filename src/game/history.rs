@@ -0,0 +1,116 @@
+use crate::board::state::BoardState;
+use crate::game::state::{GameState, RepetitionTracker};
+use crate::game::GameStatus;
+use crate::pieces::{Piece, Side};
+use crate::play::PlayRecord;
+
+/// The parts of a [`GameState`] other than the board, which cannot be recovered from the record
+/// of a play.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct StateFlags {
+    pub side_to_play: Side,
+    pub repetitions: RepetitionTracker,
+    pub plays_since_capture: usize,
+    pub attacker_captures: u16,
+    pub defender_captures: u16,
+    pub status: GameStatus,
+    pub turn: usize,
+    pub sides_swapped: bool
+}
+
+impl StateFlags {
+
+    /// Get the flags of the given state.
+    pub fn of<T: BoardState>(state: &GameState<T>) -> Self {
+        Self {
+            side_to_play: state.side_to_play,
+            repetitions: state.repetitions,
+            plays_since_capture: state.plays_since_capture,
+            attacker_captures: state.attacker_captures,
+            defender_captures: state.defender_captures,
+            status: state.status,
+            turn: state.turn,
+            sides_swapped: state.sides_swapped
+        }
+    }
+
+    /// Apply the flags to the given state.
+    pub fn restore<T: BoardState>(&self, state: &mut GameState<T>) {
+        state.side_to_play = self.side_to_play;
+        state.repetitions = self.repetitions;
+        state.plays_since_capture = self.plays_since_capture;
+        state.attacker_captures = self.attacker_captures;
+        state.defender_captures = self.defender_captures;
+        state.status = self.status;
+        state.turn = self.turn;
+        state.sides_swapped = self.sides_swapped;
+    }
+}
+
+/// An entry in the history of a [`Game`](crate::game::Game), containing the information needed
+/// (together with the corresponding [`PlayRecord`], for a play) to reverse it. No copy of the board
+/// is kept, so the memory used by a game's history is small and does not depend on the size of the
+/// board.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum HistoryEntry {
+    /// A play was made.
+    Play {
+        /// The piece that moved, before any promotion.
+        moved: Piece,
+        /// The flags of the game state before the play.
+        flags: StateFlags
+    },
+    /// The players swapped sides under the pie rule.
+    Swap
+}
+
+impl HistoryEntry {
+
+    /// Reverse the effect of this entry on the given state. `record` must be the record of the
+    /// play, if this entry is for a play.
+    pub fn reverse<T: BoardState>(&self, state: &mut GameState<T>, record: Option<&PlayRecord>) {
+        match self {
+            HistoryEntry::Play { moved, flags } => {
+                let record = record.expect("Record must be provided to reverse a play.");
+                let play = record.play;
+                state.board.clear_tile(play.to());
+                state.board.set_piece(play.from, *moved);
+                for c in &record.effects.captures {
+                    state.board.set_piece(c.tile, c.piece);
+                }
+                flags.restore(state);
+            },
+            HistoryEntry::Swap => state.sides_swapped = false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::MediumBasicGame;
+    use crate::preset::{boards, rules};
+    use crate::utils::{Rng, SplitMix64};
+
+    #[test]
+    fn test_history() {
+        let mut game = MediumBasicGame::new(rules::COPENHAGEN, boards::COPENHAGEN).unwrap();
+        let mut rng = SplitMix64::new(3);
+        let mut states = vec![game.state];
+        for _ in 0..200 {
+            let plays = game.valid_plays();
+            let Some(play) = rng.choose(&plays) else { break };
+            game.do_play(play.play).unwrap();
+            states.push(game.state);
+        }
+        assert!(game.play_history.iter().any(|r| !r.effects.captures.is_empty()));
+        for (i, state) in states.iter().enumerate() {
+            assert_eq!(game.state_at(i), Some(*state));
+        }
+        assert_eq!(game.state_at(states.len()), None);
+        for state in states.iter().rev() {
+            assert_eq!(game.state, *state);
+            game.undo_last_play();
+        }
+        assert!(game.history.is_empty());
+    }
+}
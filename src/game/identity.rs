@@ -0,0 +1,136 @@
+use crate::board::state::BoardState;
+use crate::game::Game;
+use crate::play::PlayRecord;
+use crate::tiles::Tile;
+use std::collections::HashMap;
+
+/// A stable identifier for a single piece over the course of a game.
+pub type PieceId = u16;
+
+/// The pieces involved in a single play, identified by their [`PieceId`]s.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PieceMovement {
+    /// The id of the piece that moved.
+    pub moved: PieceId,
+    /// The tile from which the piece moved.
+    pub from: Tile,
+    /// The tile to which the piece moved.
+    pub to: Tile,
+    /// The ids of the pieces captured by the play, and the tiles they were captured on.
+    pub captured: Vec<(PieceId, Tile)>
+}
+
+/// Keeps track of the identity of each piece on the board, so that (for example) a user interface
+/// can animate the piece that moved rather than working out what changed by comparing boards.
+///
+/// Each piece on the starting board is given an id, starting from zero and counting along each
+/// row in turn from the top left of the board. A piece keeps its id for the rest of the game, even
+/// if it is promoted.
+#[derive(Debug, Clone, Default)]
+pub struct PieceTracker {
+    ids: HashMap<Tile, PieceId>,
+    history: Vec<PieceMovement>
+}
+
+impl PieceTracker {
+
+    /// Create a new tracker, assigning ids to the pieces on the given starting board.
+    pub fn new<T: BoardState>(board: &T) -> Self {
+        let side_len = board.side_len();
+        let ids = (0..side_len)
+            .flat_map(|r| (0..side_len).map(move |c| Tile::new(r, c)))
+            .filter(|t| board.tile_occupied(*t))
+            .enumerate()
+            .map(|(i, t)| (t, i as PieceId))
+            .collect();
+        Self { ids, history: vec![] }
+    }
+
+    /// Create a new tracker for the given game, assigning ids to the pieces on its starting board
+    /// and applying all plays made so far.
+    pub fn from_game<T: BoardState>(game: &Game<T>) -> Self {
+        let start = game.state_at(0).expect("Starting state should always be available.");
+        let mut tracker = Self::new(&start.board);
+        for record in &game.play_history {
+            tracker.apply(record);
+        }
+        tracker
+    }
+
+    /// The id of the piece on the given tile, if any.
+    pub fn id_at(&self, tile: Tile) -> Option<PieceId> {
+        self.ids.get(&tile).copied()
+    }
+
+    /// The tile occupied by the piece with the given id, or `None` if it has been captured (or
+    /// never existed).
+    pub fn tile_of(&self, id: PieceId) -> Option<Tile> {
+        self.ids.iter().find(|(_, i)| **i == id).map(|(t, _)| *t)
+    }
+
+    /// Update the tracker following the given play, returning the ids of the pieces involved.
+    /// Panics if there is no known piece at the play's starting tile.
+    pub fn apply(&mut self, record: &PlayRecord) -> PieceMovement {
+        let from = record.play.from;
+        let to = record.play.to();
+        let moved = self.ids.remove(&from).expect("No piece found at start of play.");
+        self.ids.insert(to, moved);
+        let captured = record.effects.captures.iter()
+            .filter_map(|c| self.ids.remove(&c.tile).map(|id| (id, c.tile)))
+            .collect();
+        let movement = PieceMovement { moved, from, to, captured };
+        self.history.push(movement.clone());
+        movement
+    }
+
+    /// Reverse the last play applied to the tracker, returning the ids of the pieces involved.
+    pub fn undo(&mut self) -> Option<PieceMovement> {
+        let movement = self.history.pop()?;
+        for (id, tile) in &movement.captured {
+            self.ids.insert(*tile, *id);
+        }
+        self.ids.remove(&movement.to);
+        self.ids.insert(movement.from, movement.moved);
+        Some(movement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::identity::PieceTracker;
+    use crate::game::SmallBasicGame;
+    use crate::play::Play;
+    use crate::preset::rules;
+    use crate::tiles::Tile;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_piece_tracker() {
+        let mut game = SmallBasicGame::new(rules::BRANDUBH, "7/1t5/1T5/7/7/2t4/3K3").unwrap();
+        let mut tracker = PieceTracker::new(&game.state.board);
+        assert_eq!(tracker.id_at(Tile::new(1, 1)), Some(0));
+        assert_eq!(tracker.id_at(Tile::new(2, 1)), Some(1));
+        assert_eq!(tracker.id_at(Tile::new(5, 2)), Some(2));
+        assert_eq!(tracker.id_at(Tile::new(6, 3)), Some(3));
+
+        game.do_play(Play::from_str("c6-c4").unwrap()).unwrap();
+        let movement = tracker.apply(game.play_history.last().unwrap());
+        assert_eq!(movement.moved, 2);
+        assert_eq!(movement.captured, vec![]);
+        game.do_play(Play::from_str("d7-e7").unwrap()).unwrap();
+        tracker.apply(game.play_history.last().unwrap());
+        game.do_play(Play::from_str("c4-b4").unwrap()).unwrap();
+        let movement = tracker.apply(game.play_history.last().unwrap());
+        assert_eq!(movement.moved, 2);
+        assert_eq!(movement.captured, vec![(1, Tile::new(2, 1))]);
+        assert_eq!(tracker.tile_of(2), Some(Tile::new(3, 1)));
+        assert_eq!(tracker.tile_of(1), None);
+
+        let from_game = PieceTracker::from_game(&game);
+        assert_eq!(from_game.ids, tracker.ids);
+
+        tracker.undo();
+        assert_eq!(tracker.id_at(Tile::new(2, 1)), Some(1));
+        assert_eq!(tracker.id_at(Tile::new(3, 2)), Some(2));
+    }
+}
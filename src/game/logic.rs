@@ -1,20 +1,21 @@
 use crate::board::geometry::BoardGeometry;
 use crate::board::state::BoardState;
-use crate::error::PlayInvalid::{BlockedByPiece, GameOver, MoveOntoBlockedTile, MoveThroughBlockedTile, NoCommonAxis, NoPiece, OutOfBounds, TooFar, WrongPlayer};
+use crate::error::PlayInvalid::{BlockedByPiece, FirstPlayRestricted, GameOver, MoveOntoBlockedTile, MoveThroughBlockedTile, NoCommonAxis, NoPiece, OutOfBounds, TooFar, WrongPlayer};
 use crate::error::{BoardError, PlayInvalid};
-use crate::game::state::GameState;
+use crate::game::movegen::StagedPlayIterator;
+use crate::game::state::{GameState, RepetitionStatus};
 use crate::game::GameOutcome::{Draw, Win};
 use crate::game::GameStatus::{Ongoing, Over};
-use crate::game::WinReason::{AllCaptured, Enclosed, ExitFort, KingCaptured, KingEscaped};
-use crate::game::{DrawReason, GameOutcome, PlayEffects, WinReason};
-use crate::pieces::PieceType::{King, Soldier};
+use crate::game::WinReason::{AllCaptured, CaptureCount, Enclosed, ExitFort, KingCaptured, KingEscaped, KingReachedTarget, ThroneOccupied};
+use crate::game::{Captures, DrawReason, GameOutcome, PlayEffects, WinReason};
+use crate::pieces::PieceType::{Commander, Guard, King, Soldier};
 use crate::pieces::Side::{Attacker, Defender};
 use crate::pieces::{Piece, PieceSet, PlacedPiece, Side, KING};
 use crate::play::{Play, ValidPlayIterator, PlayRecord, ValidPlay};
 use crate::rules::EnclosureWinRules::WithoutEdgeAccess;
 use crate::rules::KingAttack::{Anvil, Armed, Hammer};
 use crate::rules::ThroneRule::{KingEntry, KingPass, NoEntry, NoPass, NoThrone};
-use crate::rules::{KingStrength, RepetitionRule, Ruleset, ShieldwallRules};
+use crate::rules::{GuardCapture, KingStrength, KingTarget, RepetitionRule, Ruleset, ShieldwallRules, SpecialTileRules};
 use crate::tiles::Axis::{Horizontal, Vertical};
 use crate::tiles::{Axis, AxisOffset, Coords, RowColOffset, Tile};
 use crate::utils::UniqueStack;
@@ -58,17 +59,108 @@ impl<T: BoardState> From<DoPlayResult<T>> for (GameState<T>, PlayRecord) {
 /// The information stored in this struct is not expected to change over the course of a game. It
 /// does not contain the current game state (piece placement, number of repetitions, etc), but
 /// rather, its methods take references to such state where necessary.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct GameLogic {
     pub rules: Ruleset,
-    pub board_geo: BoardGeometry
+    pub board_geo: BoardGeometry,
+    escape_lines: LineMasks
+}
+
+/// For each row and column of the board, a bitmask of the tiles in that row or column which have
+/// some property (with bit `n` representing the tile in column or row `n`, respectively).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct LineMasks {
+    rows: [u32; 32],
+    cols: [u32; 32]
+}
+
+impl LineMasks {
+
+    fn new(side_len: u8, predicate: impl Fn(Tile) -> bool) -> Self {
+        let mut masks = Self { rows: [0; 32], cols: [0; 32] };
+        for r in 0..side_len {
+            for c in 0..side_len {
+                if predicate(Tile::new(r, c)) {
+                    masks.rows[r as usize] |= 1 << c;
+                    masks.cols[c as usize] |= 1 << r;
+                }
+            }
+        }
+        masks
+    }
 }
 
 impl GameLogic {
 
     /// Create a new [`GameLogic`] struct from the given rules and starting positions.
     pub fn new(rules: Ruleset, board_length: u8) -> Self {
-        Self { rules, board_geo: BoardGeometry::new(board_length) }
+        let board_geo = BoardGeometry::new(board_length);
+        let escape_lines = LineMasks::new(board_length, |t| match rules.king_target {
+            Some(KingTarget::Throne) => t == board_geo.special_tiles.throne,
+            Some(KingTarget::Tile(target)) => t == target,
+            None if rules.edge_escape => board_geo.tile_at_edge(t),
+            None => board_geo.special_tiles.corners.contains(&t)
+        });
+        Self { rules, board_geo, escape_lines }
+    }
+
+    /// Whether the king wins the game by moving to the given tile (other than through an exit
+    /// fort).
+    pub fn is_escape_tile(&self, tile: Tile) -> bool {
+        (tile.row < 32) && (self.escape_lines.rows[tile.row as usize] & (1 << tile.col) != 0)
+    }
+
+    /// Get every play by which the king could immediately escape (or reach its target tile), if
+    /// it were the defender's turn to play. Exit forts are not considered.
+    ///
+    /// This is much faster than checking the outcome of each of the king's plays: only rows and
+    /// columns of the board which contain an escape tile are searched.
+    pub fn king_escapes<T: BoardState>(&self, state: &GameState<T>) -> Vec<ValidPlay> {
+        let king = state.board.get_king();
+        if state.board.get_piece(king) != Some(KING) {
+            return vec![]
+        }
+        let state = GameState { side_to_play: Defender, ..*state };
+        let row_mask = self.escape_lines.rows[king.row as usize];
+        let col_mask = self.escape_lines.cols[king.col as usize];
+        let below = |i: u8| (1u32 << i) - 1;
+        let mut escapes = vec![];
+        for (axis, mask, pos) in [(Vertical, col_mask, king.row), (Horizontal, row_mask, king.col)] {
+            for (direction, dir_mask) in [(1i8, mask & !below(pos + 1)), (-1, mask & below(pos))] {
+                if dir_mask == 0 {
+                    // No escape tiles in this direction.
+                    continue
+                }
+                let mut displacement = direction;
+                loop {
+                    let play = Play::new(king, AxisOffset::new(axis, displacement));
+                    if !self.board_geo.coords_in_bounds(play.to_coords()) {
+                        break
+                    }
+                    let (can_occupy, can_pass) = self.can_occupy_or_pass(play, KING, &state);
+                    if can_occupy && self.is_escape_tile(play.to()) {
+                        escapes.push(ValidPlay { play });
+                    }
+                    if !can_pass {
+                        break
+                    }
+                    displacement += direction;
+                }
+            }
+        }
+        escapes
+    }
+
+    /// Whether the king could immediately escape (or reach its target tile), if it were the
+    /// defender's turn to play. See [`Self::king_escapes`].
+    pub fn king_can_escape<T: BoardState>(&self, state: &GameState<T>) -> bool {
+        !self.king_escapes(state).is_empty()
+    }
+
+    /// Get the rules for the class of special tile (other than the throne and corners) to which
+    /// the given tile belongs, if any.
+    pub fn special_tile_class(&self, tile: Tile) -> Option<&SpecialTileRules> {
+        self.rules.special_tiles.iter().find(|c| c.tiles.contains(&tile))
     }
 
     /// Determine whether the given tile is hostile specifically by reference to the rules regarding
@@ -79,6 +171,18 @@ impl GameLogic {
             && self.board_geo.special_tiles.corners.contains(&tile))
             || (self.rules.hostility.edge.contains(piece)
             && !self.board_geo.tile_in_bounds(tile))
+            || self.special_tile_class(tile).is_some_and(|c| c.hostile_to.contains(piece))
+    }
+
+    /// Whether the rules for special tile classes permit the given piece, starting its move at
+    /// `from`, to stop on (if `stop` is `true`) or pass through (otherwise) the given tile.
+    fn special_tile_permits(&self, tile: Tile, from: Tile, piece: Piece, stop: bool) -> bool {
+        match self.special_tile_class(tile) {
+            None => true,
+            Some(c) => (c.residents_exempt && c.tiles.contains(&from))
+                || (stop && c.may_enter.contains(piece))
+                || (!stop && c.may_pass.contains(piece))
+        }
     }
 
     /// Determine whether the given tile is hostile to the given piece.
@@ -122,7 +226,7 @@ impl GameLogic {
         piece: Piece,
         state: &GameState<T>
     ) -> (bool, bool) {
-        let validity = self.validate_play_for_side(play, piece.side, state);
+        let validity = self.check_play_for_side(play, piece.side, state);
         let can_occupy = validity.is_ok();
         let can_pass = match validity {
             Ok(_) => true,
@@ -141,11 +245,17 @@ impl GameLogic {
                         },
                         KingEntry => true,
                     }
+                } else if self.special_tile_class(play.to()).is_some() {
+                    self.special_tile_permits(play.to(), play.from, piece, false)
+                        && !self.board_geo.special_tiles.corners.contains(&play.to())
                 } else {
-                    // If special tile is not a throne, it must be a corner, so cannot be passed.
+                    // If special tile is not a throne or one of the special tiles specified by the
+                    // rules, it must be a corner, so cannot be passed.
                     false
                 }
             },
+            // A restriction on the first play prevents stopping on a tile, not passing through it.
+            Err(FirstPlayRestricted) => true,
             _ => {
                 false
             }
@@ -156,11 +266,27 @@ impl GameLogic {
     /// Check whether a play is valid for the given side. Returns a `Result` which contains a
     /// [`ValidPlay`] wrapping the given `Play` if it is valid, and an [`PlayInvalid`] describing
     ///the reason for the invalidity otherwise. 
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self, state), ret))]
     pub fn validate_play_for_side<T: BoardState>(
         &self,
         play: Play,
         side: Side,
         state: &GameState<T>
+    ) -> Result<ValidPlay, PlayInvalid> {
+        let result = self.check_play_for_side(play, side, state);
+        #[cfg(feature = "tracing")]
+        if let Err(reason) = &result {
+            tracing::debug!(%play, ?side, ?reason, "play is invalid");
+        }
+        result
+    }
+
+    /// Check whether a play is valid for the given side (see [`Self::validate_play_for_side`]).
+    fn check_play_for_side<T: BoardState>(
+        &self,
+        play: Play,
+        side: Side,
+        state: &GameState<T>
     ) -> Result<ValidPlay, PlayInvalid> {
         if state.status != Ongoing {
             return Err(GameOver)
@@ -191,6 +317,12 @@ impl GameLogic {
                     self.board_geo.special_tiles.corners.contains(&to) {
                     return Err(MoveOntoBlockedTile)
                 }
+                if !self.special_tile_permits(to, from, piece, true) {
+                    return Err(MoveOntoBlockedTile)
+                }
+                if between.iter().any(|t| !self.special_tile_permits(*t, from, piece, false)) {
+                    return Err(MoveThroughBlockedTile)
+                }
                 if (
                     (self.rules.throne_movement == NoPass)
                         || ((self.rules.throne_movement == KingPass)
@@ -208,11 +340,48 @@ impl GameLogic {
                     // Slow piece can't move more than one space at a time
                     return Err(TooFar)
                 }
+                if self.rules.range_limits.iter()
+                    .any(|r| r.pieces.contains(piece) && play.distance() > r.max_distance) {
+                    // Piece can't move further than its range permits
+                    return Err(TooFar)
+                }
+                // Only the starting side's first play is restricted, not (for example) the replies
+                // considered when checking whether the other side can play after it.
+                if state.turn == 0 && side == self.rules.starting_side {
+                    if let Some(restrictions) = self.rules.first_play {
+                        if restrictions.forbidden_tiles.contains(&to) {
+                            return Err(FirstPlayRestricted)
+                        }
+                        if !restrictions.may_capture {
+                            let mut after = *state;
+                            let piece = after.board.move_piece(from, to);
+                            if !self.get_captures(play, piece, &after).is_empty() {
+                                return Err(FirstPlayRestricted)
+                            }
+                        }
+                    }
+                }
                 Ok(ValidPlay { play })
             }
         }
     }
 
+    /// Loosely check whether a play could be valid as a premove, ie, a play by the side not to
+    /// play, to be made as soon as it is that side's turn (which may be after more than one play,
+    /// under a [`DoublePlayRule`](crate::rules::DoublePlayRule)). Since the opponent's play may
+    /// move or capture pieces, a play blocked by a piece is accepted; otherwise the usual checks
+    /// apply. A premove must still be fully validated before it is made.
+    pub fn validate_premove<T: BoardState>(
+        &self,
+        play: Play,
+        state: &GameState<T>
+    ) -> Result<(), PlayInvalid> {
+        match self.validate_play_for_side(play, state.side_to_play.other(), state) {
+            Ok(_) | Err(BlockedByPiece) => Ok(()),
+            Err(reason) => Err(reason)
+        }
+    }
+
     /// Check whether a move is valid. Returns a `Result` which contains a [`ValidPlay`] wrapping
     /// the given `Play` if it is valid, and a [`PlayInvalid`] describing the reason for the
     /// invalidity otherwise.
@@ -262,6 +431,9 @@ impl GameLogic {
             && self.board_geo.special_tiles.corners.contains(&t) {
             return false
         }
+        if self.special_tile_class(t).is_some_and(|c| !c.may_enter.contains(piece)) {
+            return false
+        }
         true
     }
 
@@ -600,9 +772,10 @@ impl GameLogic {
         }
     }
 
-    /// Get the tiles containing pieces captured by the given play.
-    pub fn get_captures<T: BoardState>(&self, play: Play, moving_piece: Piece, state: &GameState<T>) -> HashSet<PlacedPiece> {
-        let mut captures: HashSet<PlacedPiece> = HashSet::new();
+    /// Get the pieces captured by the given play, in a deterministic order.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self, state)))]
+    pub fn get_captures<T: BoardState>(&self, play: Play, moving_piece: Piece, state: &GameState<T>) -> Captures {
+        let mut captures = Captures::new();
         let to = play.to();
 
         // Detect normal captures
@@ -640,37 +813,20 @@ impl GameLogic {
                     let far_coords = Coords { row: signed_far_row, col: signed_far_col };
                     // Check if the tile on the other side of the neighbour is a hostile tile, or if
                     // the neighbour is on the edge and the edge is treated as hostile to that piece
-                    if self.coords_hostile(far_coords, other_piece, &state.board) {
+                    if self.coords_hostile(far_coords, other_piece, &state.board)
+                        || (self.rules.commander_lone_capture
+                        && moving_piece.piece_type == Commander
+                        && self.commander_anvil(far_coords, &state.board)) {
                         // We know that the neighbouring opposing piece is surrounded by the
                         // moving piece and another hostile tile. So it is captured, *unless* it
-                        // is a strong king.
-                        if (other_piece.piece_type == King) && self.king_is_strong(&state.board) {
-                            // Get the tiles surrounding `n` on the perpendicular axis.
-                            let n_coords = Coords::from(n);
-                            let perp_hostile= if to.row == n.row {
-                                self.coords_hostile(
-                                    n_coords + RowColOffset::new(1, 0),
-                                    other_piece,
-                                    &state.board,
-                                ) && self.coords_hostile(
-                                    n_coords + RowColOffset::new(-1, 0),
-                                    other_piece,
-                                    &state.board,
-                                )
-                            } else {
-                                self.coords_hostile(
-                                    n_coords + RowColOffset::new(0, 1),
-                                    other_piece,
-                                    &state.board,
-                                ) && self.coords_hostile(
-                                    n_coords + RowColOffset::new(0, -1),
-                                    other_piece,
-                                    &state.board,
-                                )
-                            };
-                            if !perp_hostile {
-                                continue
-                            }
+                        // is a strong king or a guard which the rules protect from such capture.
+                        if (other_piece.piece_type == King) && self.king_is_strong(&state.board)
+                            && !self.perp_hostile(to, n, other_piece, &state.board) {
+                            continue
+                        }
+                        if other_piece.piece_type == Guard
+                            && !self.guard_capturable(to, n, other_piece, far_coords, &state.board) {
+                            continue
                         }
                         captures.insert(PlacedPiece { tile: n, piece: other_piece });
                     } else if self.rules.linnaean_capture && state.side_to_play == Attacker {
@@ -689,21 +845,78 @@ impl GameLogic {
 
         // Detect shieldwall captures
         if let Some(walled) = self.detect_shieldwall(play, state) {
-            captures.extend(walled.iter().map(|t| 
-                PlacedPiece { tile: *t, piece: state.board.get_piece(*t)
-                    .expect("No piece found on captured tile.") }
-            ));
+            debug_event!(%play, n_walled = walled.len(), "shieldwall detected");
+            // Sort the tiles, as the iteration order of a `HashSet` varies from run to run.
+            let mut walled: Vec<Tile> = walled.into_iter().collect();
+            walled.sort();
+            for t in walled {
+                captures.insert(PlacedPiece { tile: t, piece: state.board.get_piece(t)
+                    .expect("No piece found on captured tile.") });
+            }
         }
+        debug_event!(%play, ?captures, "captures resolved");
         captures
 
     }
 
+    /// Whether the tiles on either side of `n`, on the axis perpendicular to the line between `to`
+    /// and `n`, are both hostile to the given piece (which is assumed to be at `n`).
+    fn perp_hostile<T: BoardState>(&self, to: Tile, n: Tile, piece: Piece, board: &T) -> bool {
+        let n_coords = Coords::from(n);
+        let (a, b) = if to.row == n.row {
+            (RowColOffset::new(1, 0), RowColOffset::new(-1, 0))
+        } else {
+            (RowColOffset::new(0, 1), RowColOffset::new(0, -1))
+        };
+        self.coords_hostile(n_coords + a, piece, board) && self.coords_hostile(n_coords + b, piece, board)
+    }
+
+    /// Whether the given guard at `n`, which is between a piece that has moved to `to` and a
+    /// hostile position at `far_coords`, can be captured according to the rules.
+    fn guard_capturable<T: BoardState>(
+        &self,
+        to: Tile,
+        n: Tile,
+        guard: Piece,
+        far_coords: Coords,
+        board: &T
+    ) -> bool {
+        match self.rules.guard_capture {
+            GuardCapture::Normal => true,
+            GuardCapture::HostileTileOnly => self.board_geo.coords_to_tile(far_coords)
+                .map_or(true, |t| !board.tile_occupied(t)),
+            GuardCapture::FourSided => self.perp_hostile(to, n, guard, board)
+        }
+    }
+
+    /// Whether the position at the given coordinates can be used by a commander to capture a piece
+    /// on its own, ie, whether it is off the board or an empty special tile.
+    fn commander_anvil<T: BoardState>(&self, coords: Coords, board: &T) -> bool {
+        match self.board_geo.coords_to_tile(coords) {
+            Err(_) => true,
+            Ok(t) => !board.tile_occupied(t) && (
+                (t == self.board_geo.special_tiles.throne && self.rules.throne_movement != NoThrone)
+                    || self.board_geo.special_tiles.corners.contains(&t)
+                    || self.special_tile_class(t).is_some()
+            )
+        }
+    }
+
+    /// Get the tile the king must reach for the defender to win, under the given target rule.
+    pub fn king_target_tile(&self, target: KingTarget) -> Tile {
+        match target {
+            KingTarget::Throne => self.board_geo.special_tiles.throne,
+            KingTarget::Tile(t) => t
+        }
+    }
+
     /// Get the outcome of the game, if any. If None, the game is still ongoing.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self, state), ret))]
     pub fn get_game_outcome<T: BoardState>(
         &self,
         play: Play,
         moving_piece: Piece,
-        caps: &HashSet<PlacedPiece>,
+        caps: &Captures,
         state: &GameState<T>,
     ) -> Option<GameOutcome> {
         if state.board.count_pieces(state.side_to_play.other()) == 0 {
@@ -717,6 +930,16 @@ impl GameLogic {
                 // Attacker has captured the king.
                 return Some(Win(KingCaptured, Attacker))
             }
+            if self.rules.annihilation_win
+                && state.board.count_pieces(Defender) == 1
+                && state.board.get_piece(state.board.get_king()) == Some(KING) {
+                // Attacker has captured every defender other than the king.
+                return Some(Win(AllCaptured, Attacker))
+            }
+            if self.rules.throne_occupation_win && play.to() == self.board_geo.special_tiles.throne {
+                // Attacker has occupied the throne.
+                return Some(Win(ThroneOccupied, Attacker))
+            }
             if let Some(encl_win) = self.rules.enclosure_win {
                 if let Some(encl) = self.find_enclosure(
                     state.board.get_king(),
@@ -732,6 +955,11 @@ impl GameLogic {
                     }
                 }
             }
+        } else if let Some(target) = self.rules.king_target {
+            if moving_piece.piece_type == King && play.to() == self.king_target_tile(target) {
+                // King has reached its target.
+                return Some(Win(KingReachedTarget, Defender))
+            }
         } else {
             if moving_piece.piece_type == King && (
                 (self.rules.edge_escape && self.board_geo.tile_at_edge(play.to()))
@@ -746,6 +974,13 @@ impl GameLogic {
             }
         }
 
+        if let Some(n) = self.rules.capture_win {
+            if state.captures_by(state.side_to_play) >= n {
+                // Side has captured enough pieces to win.
+                return Some(Win(CaptureCount, state.side_to_play))
+            }
+        }
+
         if let Some(RepetitionRule { n_repetitions, is_loss }) = self.rules.repetition_rule {
             if state.repetitions.get_repetitions(state.side_to_play) >= n_repetitions {
                 // Loss or draw as a result of repeated moves.
@@ -757,12 +992,33 @@ impl GameLogic {
             }
         }
 
-        if !self.side_can_play(state.side_to_play.other(), state) {
-            // Other side has no playable moves.
+        if let Some(limit) = self.rules.move_limit {
+            // `state.turn` has not yet been incremented to account for the current play.
+            if limit.total_plays.is_some_and(|n| state.turn + 1 >= n)
+                || limit.plays_without_capture.is_some_and(|n| state.plays_since_capture >= n) {
+                // Move limit has been reached.
+                let balance = self.material_balance(state);
+                return if !limit.adjudicate_on_material || balance == 0 {
+                    Some(Draw(DrawReason::MoveLimit))
+                } else if balance > 0 {
+                    Some(Win(WinReason::MoveLimit, Attacker))
+                } else {
+                    Some(Win(WinReason::MoveLimit, Defender))
+                }
+            }
+        }
+
+        let next_side = if self.plays_again(state.side_to_play, state.turn) {
+            state.side_to_play
+        } else {
+            state.side_to_play.other()
+        };
+        if !self.side_can_play(next_side, state) {
+            // Side to play next has no playable moves.
             if self.rules.draw_on_no_plays {
                 return Some(Draw(DrawReason::NoPlays))
             } else {
-                return Some(Win(WinReason::NoPlays, state.side_to_play))
+                return Some(Win(WinReason::NoPlays, next_side.other()))
             }
 
         }
@@ -770,6 +1026,24 @@ impl GameLogic {
         None
     }
 
+    /// Whether the given side, having just made the play with the given index (counting from zero
+    /// for the first play of the game), should play again under the double play rule (if any).
+    pub fn plays_again(&self, side: Side, play_index: usize) -> bool {
+        self.rules.double_play.is_some_and(|r| {
+            // Until the double play, the sides simply alternate.
+            let offset = if side == self.rules.starting_side { 0 } else { 1 };
+            r.side == side && play_index == (r.turn * 2) + offset
+        })
+    }
+
+    /// Get the piece into which the given piece should be promoted after making the given play, if
+    /// any.
+    pub fn get_promotion(&self, play: Play, moving_piece: Piece) -> Option<Piece> {
+        self.rules.promotions.iter()
+            .find(|p| p.pieces.contains(moving_piece) && p.tiles.contains(&play.to()))
+            .map(|p| Piece::new(p.promote_to, moving_piece.side))
+    }
+
     /// Execute a known valid play. Gets the outcome of the move, applies the outcome (captures,
     /// etc) to a copy of the current game state, checks for any game end conditions, and returns
     /// the modified copy of the game state plus a record of the play (including its effects).
@@ -796,9 +1070,21 @@ impl GameLogic {
         state.repetitions.track_play(state.side_to_play, play, !captures.is_empty());
         if captures.is_empty() {
             state.plays_since_capture += 1;
+        } else {
+            state.plays_since_capture = 0;
+            match state.side_to_play {
+                Attacker => state.attacker_captures += captures.len() as u16,
+                Defender => state.defender_captures += captures.len() as u16
+            }
         }
         // Then assess the game outcome
         let game_outcome = self.get_game_outcome(play, moving_piece, &captures, &state);
+        let plays_again = self.plays_again(state.side_to_play, state.turn);
+        // Then promote the moving piece, if applicable
+        let promotion = self.get_promotion(play, moving_piece);
+        if let Some(promoted) = promotion {
+            state.board.set_piece(play.to(), promoted);
+        }
 
         state.turn += 1;
         let game_status = match game_outcome {
@@ -806,13 +1092,15 @@ impl GameLogic {
             None => Ongoing
         };
 
-        let outcome = PlayEffects { captures, game_outcome };
+        let outcome = PlayEffects { captures, promotion, game_outcome };
         let record = PlayRecord {
             side: state.side_to_play, play,
             effects: outcome
         };
 
-        state.side_to_play = state.side_to_play.other();
+        if !plays_again {
+            state.side_to_play = state.side_to_play.other();
+        }
         state.status = game_status;
 
         DoPlayResult { new_state: state, record }
@@ -833,6 +1121,27 @@ impl GameLogic {
         Ok(self.do_valid_play(valid_play, state))
     }
     
+    /// How the given play would affect the repetition count of the side to play, so that a user
+    /// interface can warn the player before the ruleset's [`RepetitionRule`] is triggered.
+    /// Returns `Ok(None)` if the rules have no repetition rule, or an error if the play is invalid.
+    pub fn repetition_status<T: BoardState>(
+        &self,
+        play: Play,
+        state: &GameState<T>
+    ) -> Result<Option<RepetitionStatus>, PlayInvalid> {
+        let Some(RepetitionRule { n_repetitions, is_loss }) = self.rules.repetition_rule else {
+            return Ok(None)
+        };
+        let side = state.side_to_play;
+        let new_state = self.do_play(play, *state)?.new_state;
+        Ok(Some(RepetitionStatus {
+            current: state.repetitions.get_repetitions(side),
+            after: new_state.repetitions.get_repetitions(side),
+            limit: n_repetitions,
+            is_loss
+        }))
+    }
+
     /// Whether the given side could make any play given the current board.
     pub fn side_can_play<T: BoardState>(&self, side: Side, state: &GameState<T>) -> bool {
         for tile in state.board.iter_occupied(side) {
@@ -855,6 +1164,39 @@ impl GameLogic {
         ValidPlayIterator::new(self, state, tile)
     }
     
+    /// Iterate over the valid plays that can be made by the side to play, in stages (see
+    /// [`StagedPlayIterator`]).
+    pub fn staged_plays<'logic, 'state, T: BoardState>(
+        &'logic self,
+        state: &'state GameState<T>
+    ) -> StagedPlayIterator<'logic, 'state, T> {
+        StagedPlayIterator::new(self, state)
+    }
+
+    /// Get every valid play that can be made by the side to play. Plays are returned in a
+    /// deterministic order.
+    pub fn valid_plays<T: BoardState>(&self, state: &GameState<T>) -> Vec<ValidPlay> {
+        state.board.iter_occupied(state.side_to_play)
+            .flat_map(|t| self.iter_plays(t, state).expect("Tile must not be empty."))
+            .collect()
+    }
+
+    /// Get the total (weighted) value of the pieces of the given side left on the board, according
+    /// to the piece values specified in the rules.
+    pub fn material<T: BoardState>(&self, side: Side, board: &T) -> i32 {
+        board.iter_occupied(side)
+            .filter_map(|t| board.get_piece(t))
+            .map(|p| self.rules.piece_values.value_of(p))
+            .sum()
+    }
+
+    /// Get the material balance of the current position, ie, the value of the attacker's pieces
+    /// less the value of the defender's pieces. A positive number means the attacker is ahead on
+    /// material and a negative number means the defender is ahead.
+    pub fn material_balance<T: BoardState>(&self, state: &GameState<T>) -> i32 {
+        self.material(Attacker, &state.board) - self.material(Defender, &state.board)
+    }
+
     /// Detect whether a "Linnaean capture" has occurred.
     fn detect_linnaean_capture<T: BoardState>(
         &self,
@@ -881,25 +1223,25 @@ impl GameLogic {
 
 #[cfg(test)]
 mod tests {
-    use crate::board::state::{BoardState, HugeBasicBoardState, LargeBasicBoardState, MediumBasicBoardState, SmallBasicBoardState};
-    use crate::error::PlayInvalid::{BlockedByPiece, MoveOntoBlockedTile, MoveThroughBlockedTile, NoPiece, OutOfBounds, TooFar};
+    use crate::board::state::{BoardState, HugeBasicBoardState, LargeBasicBoardState, MediumBasicBoardState, SmallBasicBoardState, SmallExtendedBoardState};
+    use crate::error::PlayInvalid::{BlockedByPiece, FirstPlayRestricted, MoveOntoBlockedTile, MoveThroughBlockedTile, NoPiece, OutOfBounds, TooFar};
     use crate::game::logic::GameLogic;
-    use crate::game::state::{GameState, MediumBasicGameState, SmallBasicGameState};
-    use crate::game::Game;
-    use crate::game::GameOutcome::Win;
+    use crate::game::state::{GameState, MediumBasicGameState, RepetitionStatus, SmallBasicGameState, SmallExtendedGameState};
+    use crate::game::{DrawReason, Game, WinReason};
+    use crate::game::GameOutcome::{Draw, Win};
     use crate::game::GameStatus::{Ongoing, Over};
-    use crate::game::WinReason::{KingCaptured, KingEscaped, Repetition};
-    use crate::pieces::PieceType::{King, Soldier};
+    use crate::game::WinReason::{AllCaptured, CaptureCount, KingCaptured, KingEscaped, KingReachedTarget, Repetition, ThroneOccupied};
+    use crate::pieces::PieceType::{Guard, King, Soldier};
     use crate::pieces::Side::{Attacker, Defender};
-    use crate::pieces::{Piece, PieceSet, PlacedPiece, KING};
+    use crate::pieces::{Piece, PieceSet, PlacedPiece, Side, KING};
     use crate::play::{Play, ValidPlay};
     use crate::preset::{boards, rules};
-    use crate::rules::ThroneRule::NoPass;
-    use crate::rules::{HostilityRules, Ruleset, ShieldwallRules};
-    use crate::tiles::Tile;
+    use crate::rules::ThroneRule::{KingEntry, NoPass};
+    use crate::rules::{DoublePlayRule, FirstPlayRestrictions, GuardCapture, HostilityRules, KingTarget, MoveLimitRules, PromotionRule, RangeLimit, Ruleset, ShieldwallRules, SpecialTileRules};
+    use crate::tiles::{Coords, Tile};
     use crate::utils::check_tile_vec;
     use std::str::FromStr;
-    use crate::error::PlayInvalid;
+    use crate::error::{ParseError, PlayInvalid, RulesError};
 
     const TEST_RULES: Ruleset = Ruleset {
         slow_pieces: PieceSet::from_piece_type(King),
@@ -1047,9 +1389,9 @@ mod tests {
         assert_eq!(
             logic.get_captures(play, piece, &state),
             [
-                PlacedPiece::new(Tile::new(4, 1), Piece::new(Soldier, Attacker)),
                 PlacedPiece::new(Tile::new(3, 2), Piece::new(Soldier, Attacker)),
                 PlacedPiece::new(Tile::new(5, 2), Piece::new(Soldier, Attacker)),
+                PlacedPiece::new(Tile::new(4, 1), Piece::new(Soldier, Attacker)),
             ].into()
         );
         state.board.move_piece(play.to(), play.from);
@@ -1145,6 +1487,20 @@ mod tests {
             Tile::new(6, 8)
         )));
 
+        let mut after = regular_state;
+        let piece = after.board.move_piece(m.from, m.to());
+        assert_eq!(regular_logic.get_captures(m, piece, &after), [
+            PlacedPiece::new(Tile::new(4, 8), Piece::defender(Soldier)),
+            PlacedPiece::new(Tile::new(5, 8), Piece::defender(Soldier)),
+            PlacedPiece::new(Tile::new(6, 8), Piece::defender(Soldier)),
+        ].into());
+        // Play records compare captures in order, so repeating the same play must record the
+        // captures in the same order every time.
+        let record = regular_logic.do_play(m, regular_state).unwrap().record;
+        for _ in 0..20 {
+            assert_eq!(regular_logic.do_play(m, regular_state).unwrap().record, record);
+        }
+
         let king_state: GameState<MediumBasicBoardState> = GameState::new(regular_sw_king, Attacker).unwrap();
         assert_eq!(regular_logic.detect_shieldwall(m, &king_state), Some(hashset!(
             Tile::new(4, 8),
@@ -1419,6 +1775,39 @@ mod tests {
 
         assert_eq!(game.state.status, Over(Win(Repetition, Defender)));
     }
+
+    #[test]
+    fn test_repetition_status() {
+        let mut game: Game<SmallBasicBoardState> = Game::new(
+            rules::BRANDUBH,
+            boards::BRANDUBH
+        ).unwrap();
+        let play = Play::from_str("d6-f6").unwrap();
+        let status = game.repetition_status(play).unwrap().unwrap();
+        assert_eq!(status, RepetitionStatus { current: 0, after: 0, limit: 3, is_loss: true });
+        assert!(!status.increases());
+        assert_eq!(status.remaining(), 2);
+        for _ in 0..3 {
+            game.do_play(Play::from_str("d6-f6").unwrap()).unwrap();
+            game.do_play(Play::from_str("d5-f5").unwrap()).unwrap();
+            game.do_play(Play::from_str("f6-d6").unwrap()).unwrap();
+            game.do_play(Play::from_str("f5-d5").unwrap()).unwrap();
+        }
+        let status = game.repetition_status(play).unwrap().unwrap();
+        assert!(status.increases());
+        assert!(status.triggers());
+        assert_eq!(status.remaining(), 0);
+        let other = game.repetition_status(Play::from_str("d7-e7").unwrap()).unwrap().unwrap();
+        assert!(!other.increases());
+        assert_eq!(other.after, 0);
+        assert_eq!(
+            game.repetition_status(Play::from_str("d5-e5").unwrap()),
+            Err(PlayInvalid::WrongPlayer)
+        );
+
+        let logic = GameLogic::new(Ruleset { repetition_rule: None, ..rules::BRANDUBH }, 7);
+        assert_eq!(logic.repetition_status(play, &game.state_at(0).unwrap()), Ok(None));
+    }
     
     #[test]
     fn test_strong_king_capture() {
@@ -1475,10 +1864,429 @@ mod tests {
             ).expect("Invalid play."),
             state
         ).expect("Invalid play").into();
-        assert_eq!(r.effects.captures, hashset!(PlacedPiece {
+        assert_eq!(r.effects.captures, [PlacedPiece {
             tile: Tile::new(4, 3),
             piece: Piece { piece_type: Soldier, side: Defender } 
-        }));
+        }].into());
+    }
+
+    #[test]
+    fn test_special_tile_classes() {
+        const SANCTUARIES: [SpecialTileRules; 1] = [SpecialTileRules {
+            name: "sanctuary",
+            tiles: &[Tile::new(1, 1), Tile::new(1, 5)],
+            hostile_to: PieceSet::from_piece(Piece::new(Soldier, Defender)),
+            may_enter: PieceSet::from_piece_type(King),
+            may_pass: PieceSet::from_piece_type(Soldier),
+            residents_exempt: false
+        }];
+        let rules = Ruleset { special_tiles: &SANCTUARIES, ..rules::BRANDUBH };
+        let logic = GameLogic::new(rules, 7);
+        let state = SmallBasicGameState::new("1t5/2T4/7/3K3/7/7/7", Attacker).unwrap();
+
+        // Soldiers may pass through, but not stop on, a sanctuary. The king may stop on, but not
+        // pass through, a sanctuary.
+        assert_invalid_play(logic, Play::from_str("b1-b2").unwrap(), &state, MoveOntoBlockedTile);
+        assert_valid_play(logic, Play::from_str("b1-b4").unwrap(), &state);
+        let mut def_state = state;
+        def_state.side_to_play = Defender;
+        assert_valid_play(logic, Play::from_str("c2-g2").unwrap(), &def_state);
+        let king_state = SmallBasicGameState::new("7/K6/7/7/7/7/7", Defender).unwrap();
+        assert_valid_play(logic, Play::from_str("a2-b2").unwrap(), &king_state);
+        assert_invalid_play(logic, Play::from_str("a2-d2").unwrap(), &king_state, MoveThroughBlockedTile);
+        assert!(logic.coords_occupiable(Coords::new(1, 1), KING));
+        assert!(!logic.coords_occupiable(Coords::new(1, 1), Piece::new(Soldier, Attacker)));
+        let row_1 = logic.iter_plays(Tile::new(1, 2), &def_state).unwrap()
+            .map(|vp| vp.play.to())
+            .filter(|t| t.row == 1)
+            .collect::<Vec<_>>();
+        check_tile_vec(
+            row_1,
+            vec![Tile::new(1, 0), Tile::new(1, 3), Tile::new(1, 4), Tile::new(1, 6)]
+        );
+
+        // A sanctuary is hostile to defending soldiers, so a defender beside it can be captured.
+        let state = SmallBasicGameState::new("7/7/2T4/7/2t4/7/3K3", Attacker).unwrap();
+        let (_, record) = logic.do_play(Play::from_str("c5-c4").unwrap(), state).unwrap().into();
+        assert!(record.effects.captures.is_empty());
+        let state = SmallBasicGameState::new("7/7/1T5/7/1t5/7/3K3", Attacker).unwrap();
+        let (_, record) = logic.do_play(Play::from_str("b5-b4").unwrap(), state).unwrap().into();
+        assert_eq!(
+            record.effects.captures,
+            [PlacedPiece::new(Tile::new(2, 1), Piece::new(Soldier, Defender))].into()
+        );
+    }
+
+    #[test]
+    fn test_camps() {
+        let mut game: Game<MediumBasicBoardState> = Game::new(
+            rules::ASHTON_TABLUT,
+            boards::ASHTON_TABLUT
+        ).unwrap();
+        // An attacker may leave its camp...
+        game.do_play(Play::from_str("e2-d2").unwrap()).unwrap();
+        // ...but a defender may not enter a camp...
+        assert_invalid_play(game.logic, Play::from_str("e3-e2").unwrap(), &game.state, MoveOntoBlockedTile);
+        game.do_play(Play::from_str("e3-f3").unwrap()).unwrap();
+        // ...and nor may the attacker re-enter its own camp.
+        assert_invalid_play(game.logic, Play::from_str("d2-e2").unwrap(), &game.state, MoveOntoBlockedTile);
+        // Attackers still in their camp may move within it...
+        assert_valid_play(game.logic, Play::from_str("e1-e2").unwrap(), &game.state);
+        // ...but other attackers may not pass through it.
+        assert_invalid_play(game.logic, Play::from_str("d2-f2").unwrap(), &game.state, MoveThroughBlockedTile);
+    }
+
+    #[test]
+    fn test_king_target() {
+        let logic = GameLogic::new(
+            Ruleset {
+                king_target: Some(KingTarget::Throne),
+                throne_movement: KingEntry,
+                ..rules::BRANDUBH
+            },
+            7
+        );
+        let state = SmallBasicGameState::new("7/7/7/K6/7/7/3t3", Defender).unwrap();
+        // Reaching a corner does not win the game...
+        let play = Play::from_str("a4-a1").unwrap();
+        assert_eq!(logic.do_play(play, state).unwrap().new_state.status, Ongoing);
+        // ...but reaching the throne does.
+        let play = Play::from_str("a4-d4").unwrap();
+        assert_eq!(
+            logic.do_play(play, state).unwrap().new_state.status,
+            Over(Win(KingReachedTarget, Defender))
+        );
+
+        let logic = GameLogic::new(
+            Ruleset { king_target: Some(KingTarget::Tile(Tile::new(3, 2))), ..rules::BRANDUBH },
+            7
+        );
+        let play = Play::from_str("a4-c4").unwrap();
+        assert_eq!(
+            logic.do_play(play, state).unwrap().new_state.status,
+            Over(Win(KingReachedTarget, Defender))
+        );
+    }
+
+    #[test]
+    fn test_capture_win() {
+        let logic = GameLogic::new(Ruleset { capture_win: Some(2), ..rules::BRANDUBH }, 7);
+        let state = SmallBasicGameState::new("1t5/1Tt4/7/t2K3/1T5/1t5/6T", Attacker).unwrap();
+        let state = logic.do_play(Play::from_str("a4-a2").unwrap(), state).unwrap().new_state;
+        assert_eq!(state.captures_by(Attacker), 1);
+        assert_eq!(state.status, Ongoing);
+        let state = logic.do_play(Play::from_str("d4-d5").unwrap(), state).unwrap().new_state;
+        let state = logic.do_play(Play::from_str("b1-b4").unwrap(), state).unwrap().new_state;
+        assert_eq!(state.captures_by(Attacker), 2);
+        assert_eq!(state.captures_by(Defender), 0);
+        assert_eq!(state.status, Over(Win(CaptureCount, Attacker)));
+    }
+
+    #[test]
+    fn test_range_limits() {
+        const RANGE_LIMITS: [RangeLimit; 2] = [
+            RangeLimit { pieces: PieceSet::from_piece_type(King), max_distance: 3 },
+            RangeLimit { pieces: PieceSet::from_piece(Piece::new(Soldier, Attacker)), max_distance: 2 }
+        ];
+        let logic = GameLogic::new(Ruleset { range_limits: &RANGE_LIMITS, ..rules::BRANDUBH }, 7);
+        let state = SmallBasicGameState::new("7/7/7/1K5/7/7/5t1", Defender).unwrap();
+        assert_valid_play(logic, Play::from_str("b4-e4").unwrap(), &state);
+        assert_invalid_play(logic, Play::from_str("b4-f4").unwrap(), &state, TooFar);
+        let king_plays: Vec<Play> = logic.iter_plays(Tile::new(3, 1), &state).unwrap()
+            .map(|vp| vp.play)
+            .collect();
+        assert_eq!(king_plays.len(), 10);
+        assert!(king_plays.iter().all(|p| p.distance() <= 3));
+
+        let mut state = state;
+        state.side_to_play = Attacker;
+        assert_valid_play(logic, Play::from_str("f7-d7").unwrap(), &state);
+        assert_invalid_play(logic, Play::from_str("f7-c7").unwrap(), &state, TooFar);
+        assert_eq!(logic.iter_plays(Tile::new(6, 5), &state).unwrap().count(), 4);
+    }
+
+    #[test]
+    fn test_commander_lone_capture() {
+        let rules = Ruleset { commander_lone_capture: true, ..rules::BRANDUBH };
+        let state = SmallExtendedGameState::new("3T3/6c/7/5K1/7/7/7", Attacker).unwrap();
+        let play = Play::from_str("g2-d2").unwrap();
+
+        let logic = GameLogic::new(rules, 7);
+        let record = logic.do_play(play, state).unwrap().record;
+        assert_eq!(
+            record.effects.captures,
+            [PlacedPiece::new(Tile::new(0, 3), Piece::new(Soldier, Defender))].into()
+        );
+
+        // Rule is not enabled
+        let logic = GameLogic::new(rules::BRANDUBH, 7);
+        assert!(logic.do_play(play, state).unwrap().record.effects.captures.is_empty());
+
+        // Ordinary soldiers cannot capture alone
+        let logic = GameLogic::new(rules, 7);
+        let state = SmallExtendedGameState::new("3T3/6t/7/5K1/7/7/7", Attacker).unwrap();
+        assert!(logic.do_play(play, state).unwrap().record.effects.captures.is_empty());
+    }
+
+    #[test]
+    fn test_guard_capture() {
+        let hostility = HostilityRules {
+            throne: PieceSet::all(),
+            corners: PieceSet::all(),
+            edge: PieceSet::none()
+        };
+        let flank_play = Play::from_str("a6-b6").unwrap();
+        let throne_play = Play::from_str("a4-b4").unwrap();
+        let cases = [
+            // Guard flanked by two attackers
+            ("7/5K1/7/7/7/t1Gt3/7", flank_play),
+            // Guard against the empty throne
+            ("7/5K1/7/t1G4/7/7/7", throne_play),
+            // Guard against the empty throne and with attackers on either side
+            ("7/5K1/2t4/t1G4/2t4/7/7", throne_play)
+        ];
+        for (guard_capture, expected) in [
+            (GuardCapture::Normal, [true, true, true]),
+            (GuardCapture::HostileTileOnly, [false, true, true]),
+            (GuardCapture::FourSided, [false, false, true])
+        ] {
+            let logic = GameLogic::new(Ruleset { guard_capture, hostility, ..rules::BRANDUBH }, 7);
+            for ((fen, play), exp) in cases.iter().zip(expected) {
+                let state = SmallExtendedGameState::new(fen, Attacker).unwrap();
+                let caps = logic.do_play(*play, state).unwrap().record.effects.captures;
+                assert_eq!(!caps.is_empty(), exp, "{guard_capture:?}: {fen}");
+            }
+        }
+        // Ordinary soldiers are unaffected
+        let logic = GameLogic::new(
+            Ruleset { guard_capture: GuardCapture::FourSided, hostility, ..rules::BRANDUBH },
+            7
+        );
+        let state = SmallExtendedGameState::new("7/5K1/7/7/7/t1Tt3/7", Attacker).unwrap();
+        assert_eq!(logic.do_play(flank_play, state).unwrap().record.effects.captures.len(), 1);
+    }
+
+    #[test]
+    fn test_throne_occupation_win() {
+        let rules = Ruleset { throne_occupation_win: true, throne_movement: NoPass, ..rules::BRANDUBH };
+        let logic = GameLogic::new(rules, 7);
+        let state = SmallBasicGameState::new("7/1K5/7/t6/7/7/7", Attacker).unwrap();
+        let play = Play::from_str("a4-c4").unwrap();
+        assert_eq!(logic.do_play(play, state).unwrap().new_state.status, Ongoing);
+        let play = Play::from_str("a4-d4").unwrap();
+        assert_eq!(
+            logic.do_play(play, state).unwrap().new_state.status,
+            Over(Win(ThroneOccupied, Attacker))
+        );
+
+        // Defender moving onto the throne does not trigger the win
+        let state = SmallBasicGameState::new("7/1K5/7/T6/7/7/6t", Defender).unwrap();
+        assert_eq!(logic.do_play(play, state).unwrap().new_state.status, Ongoing);
+    }
+
+    #[test]
+    fn test_promotion() {
+        const PROMOTIONS: [PromotionRule; 1] = [PromotionRule {
+            pieces: PieceSet::from_piece_type(Soldier),
+            tiles: &[Tile::new(0, 3), Tile::new(6, 3)],
+            promote_to: Guard
+        }];
+        let logic = GameLogic::new(Ruleset { promotions: &PROMOTIONS, ..rules::BRANDUBH }, 7);
+        let state = SmallExtendedGameState::new("7/1K5/7/7/7/5t1/7", Attacker).unwrap();
+        let (state, record) = logic.do_play(Play::from_str("f6-d6").unwrap(), state).unwrap().into();
+        assert_eq!(record.effects.promotion, None);
+        assert_eq!(state.board.get_piece(Tile::new(5, 3)), Some(Piece::new(Soldier, Attacker)));
+        let state = logic.do_play(Play::from_str("b2-b1").unwrap(), state).unwrap().new_state;
+        let (state, record) = logic.do_play(Play::from_str("d6-d7").unwrap(), state).unwrap().into();
+        assert_eq!(record.effects.promotion, Some(Piece::new(Guard, Attacker)));
+        assert_eq!(state.board.get_piece(Tile::new(6, 3)), Some(Piece::new(Guard, Attacker)));
+        // The king is not promoted
+        let (_, record) = logic.do_play(Play::from_str("b1-d1").unwrap(), state).unwrap().into();
+        assert_eq!(record.effects.promotion, None);
+    }
+
+    #[test]
+    fn test_unsupported_promotion() {
+        const TO_GUARD: [PromotionRule; 1] = [PromotionRule {
+            pieces: PieceSet::from_piece_type(Soldier),
+            tiles: &[Tile::new(0, 3)],
+            promote_to: Guard
+        }];
+        const TO_KING: [PromotionRule; 1] = [PromotionRule {
+            pieces: PieceSet::from_piece_type(Soldier),
+            tiles: &[Tile::new(0, 3)],
+            promote_to: King
+        }];
+        let rules = Ruleset { promotions: &TO_GUARD, ..rules::BRANDUBH };
+        // A basic board cannot store guards, so the promotion could never be applied.
+        assert_eq!(
+            Game::<SmallBasicBoardState>::new(rules, boards::BRANDUBH).err(),
+            Some(ParseError::BadRules(RulesError::UnsupportedPromotion(Guard)))
+        );
+        assert!(Game::<SmallExtendedBoardState>::new(rules, boards::BRANDUBH).is_ok());
+        let rules = Ruleset { promotions: &TO_KING, ..rules::BRANDUBH };
+        assert_eq!(
+            Game::<SmallExtendedBoardState>::new(rules, boards::BRANDUBH).err(),
+            Some(ParseError::BadRules(RulesError::PromotionToKing))
+        );
+    }
+
+    #[test]
+    fn test_first_play_restrictions() {
+        const FORBIDDEN: [Tile; 2] = [Tile::new(0, 2), Tile::new(3, 2)];
+        let rules = Ruleset {
+            first_play: Some(FirstPlayRestrictions {
+                forbidden_tiles: &FORBIDDEN,
+                may_capture: false
+            }),
+            ..rules::BRANDUBH
+        };
+        let mut game: Game<SmallBasicBoardState> = Game::new(rules, "7/7/7/t6/7/2K4/1tT1t2").unwrap();
+        assert_invalid_play(game.logic, Play::from_str("a4-c4").unwrap(), &game.state, FirstPlayRestricted);
+        // Capturing play
+        assert_invalid_play(game.logic, Play::from_str("e7-d7").unwrap(), &game.state, FirstPlayRestricted);
+        let plays: Vec<Play> = game.iter_plays(Tile::new(3, 0)).unwrap().map(|vp| vp.play).collect();
+        assert!(plays.contains(&Play::from_str("a4-b4").unwrap()));
+        assert!(!plays.contains(&Play::from_str("a4-c4").unwrap()));
+        assert!(plays.contains(&Play::from_str("a4-e4").unwrap()));
+        game.do_play(Play::from_str("a4-b4").unwrap()).unwrap();
+        // Restrictions only apply to the first play.
+        game.do_play(Play::from_str("c6-c5").unwrap()).unwrap();
+        assert_valid_play(game.logic, Play::from_str("b4-c4").unwrap(), &game.state);
+    }
+
+    #[test]
+    fn test_first_play_restrictions_reply() {
+        const FORBIDDEN: [Tile; 1] = [Tile::new(0, 0)];
+        let rules = Ruleset {
+            first_play: Some(FirstPlayRestrictions {
+                forbidden_tiles: &FORBIDDEN,
+                may_capture: true
+            }),
+            ..rules::BRANDUBH
+        };
+        // The king's only play is onto a tile forbidden to the first play, which should not
+        // prevent it from replying to the first play.
+        let mut game: Game<SmallBasicBoardState> = Game::new(rules, "7/Kt5/t6/7/7/7/6t").unwrap();
+        assert_eq!(game.do_play(Play::from_str("g7-g6").unwrap()), Ok(Ongoing));
+        assert_eq!(game.do_play(Play::from_str("a2-a1").unwrap()), Ok(Over(Win(KingEscaped, Defender))));
+        let start = game.state_at(0).unwrap();
+        assert_eq!(game.logic.validate_premove(Play::from_str("a2-a1").unwrap(), &start), Ok(()));
+    }
+
+    #[test]
+    fn test_move_limit() {
+        let limit = MoveLimitRules {
+            total_plays: Some(4),
+            plays_without_capture: None,
+            adjudicate_on_material: false
+        };
+        let logic = GameLogic::new(Ruleset { move_limit: Some(limit), ..rules::BRANDUBH }, 7);
+        let mut state = SmallBasicGameState::new("7/1K5/7/7/5t1/7/5t1", Attacker).unwrap();
+        for (i, play) in ["f5-e5", "b2-c2", "e5-f5", "c2-b2"].iter().enumerate() {
+            assert_eq!(state.status, Ongoing, "{i}");
+            state = logic.do_play(Play::from_str(play).unwrap(), state).unwrap().new_state;
+        }
+        assert_eq!(state.status, Over(Draw(DrawReason::MoveLimit)));
+
+        // Plays without capture, adjudicated on material
+        let limit = MoveLimitRules {
+            total_plays: None,
+            plays_without_capture: Some(3),
+            adjudicate_on_material: true
+        };
+        let logic = GameLogic::new(Ruleset { move_limit: Some(limit), ..rules::BRANDUBH }, 7);
+        let state = SmallBasicGameState::new("7/1K5/5T1/7/6t/1T5/1t3t1", Attacker).unwrap();
+        let state = logic.do_play(Play::from_str("g5-b5").unwrap(), state).unwrap().new_state;
+        assert_eq!(state.plays_since_capture, 0);
+        let state = logic.do_play(Play::from_str("b2-c2").unwrap(), state).unwrap().new_state;
+        let state = logic.do_play(Play::from_str("f7-e7").unwrap(), state).unwrap().new_state;
+        assert_eq!(state.plays_since_capture, 2);
+        assert_eq!(state.status, Ongoing);
+        let state = logic.do_play(Play::from_str("c2-b2").unwrap(), state).unwrap().new_state;
+        assert_eq!(state.plays_since_capture, 3);
+        assert_eq!(state.status, Over(Win(WinReason::MoveLimit, Attacker)));
+    }
+
+    #[test]
+    fn test_double_play() {
+        let rules = Ruleset {
+            double_play: Some(DoublePlayRule { side: Defender, turn: 1 }),
+            ..rules::BRANDUBH
+        };
+        let mut game: Game<SmallBasicBoardState> = Game::new(rules, boards::BRANDUBH).unwrap();
+        let sides: Vec<Side> = ["d1-c1", "d3-c3", "c1-d1", "c3-b3", "b3-b2", "d1-c1", "b2-b3"]
+            .iter()
+            .map(|p| {
+                let side = game.state.side_to_play;
+                game.do_play(Play::from_str(p).unwrap()).unwrap();
+                side
+            })
+            .collect();
+        assert_eq!(sides, [Attacker, Defender, Attacker, Defender, Defender, Attacker, Defender]);
+    }
+
+    #[test]
+    fn test_annihilation_win() {
+        let state = SmallBasicGameState::new("7/1K5/7/7/3t3/7/4Tt1", Attacker).unwrap();
+        let capture = Play::from_str("d5-d7").unwrap();
+        let logic = GameLogic::new(Ruleset { annihilation_win: true, ..rules::BRANDUBH }, 7);
+        assert_eq!(
+            logic.do_play(capture, state).unwrap().new_state.status,
+            Over(Win(AllCaptured, Attacker))
+        );
+        let logic = GameLogic::new(rules::BRANDUBH, 7);
+        assert_eq!(logic.do_play(capture, state).unwrap().new_state.status, Ongoing);
+    }
+
+    #[test]
+    fn test_material_balance() {
+        let logic = GameLogic::new(rules::COPENHAGEN, 11);
+        let state = MediumBasicGameState::new(boards::COPENHAGEN, Attacker).unwrap();
+        assert_eq!(logic.material(Attacker, &state.board), 24);
+        assert_eq!(logic.material(Defender, &state.board), 24);
+        assert_eq!(logic.material_balance(&state), 0);
+
+        let logic = GameLogic::new(rules::BRANDUBH, 7);
+        let state = SmallBasicGameState::new("3t3/3t3/7/ttTK1tt/3T3/3t3/3t3", Attacker).unwrap();
+        assert_eq!(logic.material_balance(&state), 4);
+        let state = SmallBasicGameState::new("3t3/7/3T3/ttTKTtt/3T3/3t3/3t3", Attacker).unwrap();
+        assert_eq!(logic.material_balance(&state), -1);
+    }
+
+    #[test]
+    fn test_king_escapes() {
+        let logic = GameLogic::new(rules::BRANDUBH, 7);
+        let escapes = |state: &SmallBasicGameState| {
+            let mut plays: Vec<Play> = logic.king_escapes(state).into_iter()
+                .map(|vp| vp.play)
+                .collect();
+            plays.sort();
+            plays
+        };
+
+        let state = SmallBasicGameState::new("3K1t1/7/7/7/7/7/7", Attacker).unwrap();
+        assert!(logic.is_escape_tile(Tile::new(0, 0)));
+        assert!(!logic.is_escape_tile(Tile::new(0, 1)));
+        assert_eq!(escapes(&state), vec![Play::from_str("d1-a1").unwrap()]);
+        assert!(logic.king_can_escape(&state));
+
+        let state = SmallBasicGameState::new("1t1K1t1/7/7/7/7/7/7", Defender).unwrap();
+        assert!(escapes(&state).is_empty());
+        assert!(!logic.king_can_escape(&state));
+
+        let state = SmallBasicGameState::new("7/7/7/3K3/7/7/7", Defender).unwrap();
+        assert!(!logic.king_can_escape(&state));
+
+        let logic = GameLogic::new(Ruleset { edge_escape: true, ..rules::BRANDUBH }, 7);
+        let state = SmallBasicGameState::new("7/7/7/1t1K3/7/7/3t3", Defender).unwrap();
+        let mut plays: Vec<Play> = logic.king_escapes(&state).into_iter().map(|vp| vp.play).collect();
+        plays.sort();
+        let mut expected: Vec<Play> = ["d4-d1", "d4-g4"].iter()
+            .map(|s| Play::from_str(s).unwrap())
+            .collect();
+        expected.sort();
+        assert_eq!(plays, expected);
     }
 
 }
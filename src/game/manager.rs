@@ -0,0 +1,236 @@
+use crate::board::state::BoardState;
+use crate::error::ManagerError;
+use crate::error::ManagerError::NoSuchGame;
+use crate::game::clock::{Clock, TimeControl};
+use crate::game::events::GameUpdate;
+use crate::game::GameOutcome::Win;
+use crate::game::GameStatus::{Ongoing, Over};
+use crate::game::WinReason::Timeout;
+use crate::game::{Game, GameOutcome, GameStatus};
+use crate::play::{Play, PlayRecord};
+use std::collections::HashMap;
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+/// An identifier for a game owned by a [`GameManager`].
+pub type GameId = u64;
+
+/// An event relating to one of the games owned by a [`GameManager`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameEvent {
+    /// A game has been added to the manager.
+    Created { id: GameId },
+    /// A play has been made in a game.
+    Played { id: GameId, record: PlayRecord },
+    /// A game has ended, either as a result of a play or because a side ran out of time.
+    Ended { id: GameId, outcome: GameOutcome },
+    /// A game has been removed from the manager.
+    Removed { id: GameId }
+}
+
+/// A trait for types that want to be notified of [`GameEvent`]s. It is implemented for any
+/// closure taking a reference to a `GameEvent`.
+pub trait GameEventListener {
+    /// Handle an event.
+    fn on_event(&mut self, event: &GameEvent);
+}
+
+impl<F: FnMut(&GameEvent)> GameEventListener for F {
+    fn on_event(&mut self, event: &GameEvent) {
+        self(event)
+    }
+}
+
+/// A game owned by a [`GameManager`], together with its clock (if the game is timed).
+#[derive(Clone)]
+pub struct ManagedGame<T: BoardState> {
+    pub game: Game<T>,
+    pub clock: Option<Clock>
+}
+
+/// Owns a number of concurrent games, routes plays to the right game, keeps each game's clock on
+/// a shared timer and notifies listeners of events. Listeners must be [`Send`], so that the
+/// manager can be moved to (for example) a server's worker thread.
+///
+/// The manager does not read the system time itself. Instead, the owner should call
+/// [`Self::tick`] regularly with the time elapsed since the last call, which is deducted from the
+/// clock of the side to play in every ongoing timed game.
+#[derive(Default)]
+pub struct GameManager<T: BoardState> {
+    games: HashMap<GameId, ManagedGame<T>>,
+    next_id: GameId,
+    listeners: Vec<Box<dyn GameEventListener + Send>>
+}
+
+impl<T: BoardState> GameManager<T> {
+
+    /// Create a new manager with no games.
+    pub fn new() -> Self {
+        Self { games: HashMap::new(), next_id: 0, listeners: vec![] }
+    }
+
+    fn emit(&mut self, event: GameEvent) {
+        for listener in &mut self.listeners {
+            listener.on_event(&event);
+        }
+    }
+
+    /// Register a listener to be notified of all future events.
+    pub fn add_listener(&mut self, listener: impl GameEventListener + Send + 'static) {
+        self.listeners.push(Box::new(listener));
+    }
+
+    /// Add a game to the manager, timed according to the given time control (if any). Returns the
+    /// id of the new game.
+    pub fn add_game(&mut self, game: Game<T>, time_control: Option<TimeControl>) -> GameId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.games.insert(id, ManagedGame { game, clock: time_control.map(Clock::new) });
+        self.emit(GameEvent::Created { id });
+        id
+    }
+
+    /// Remove the game with the given id from the manager, returning it if it existed.
+    pub fn remove_game(&mut self, id: GameId) -> Option<ManagedGame<T>> {
+        let removed = self.games.remove(&id);
+        if removed.is_some() {
+            self.emit(GameEvent::Removed { id });
+        }
+        removed
+    }
+
+    /// Get the game with the given id.
+    pub fn get(&self, id: GameId) -> Option<&ManagedGame<T>> {
+        self.games.get(&id)
+    }
+
+    /// Subscribe to updates about the game with the given id (see [`Game::events`]), or return
+    /// `None` if there is no such game.
+    pub fn events(&mut self, id: GameId) -> Option<Receiver<GameUpdate>> {
+        self.games.get_mut(&id).map(|m| m.game.events())
+    }
+
+    /// Iterate over the ids of all games owned by the manager. Order of iteration is not
+    /// guaranteed.
+    pub fn ids(&self) -> impl Iterator<Item=GameId> + '_ {
+        self.games.keys().copied()
+    }
+
+    /// The number of games owned by the manager.
+    pub fn len(&self) -> usize {
+        self.games.len()
+    }
+
+    /// Whether the manager owns no games.
+    pub fn is_empty(&self) -> bool {
+        self.games.is_empty()
+    }
+
+    /// Make a play in the game with the given id. If the game is timed, the increment is added to
+    /// the clock of the side that made the play.
+    pub fn do_play(&mut self, id: GameId, play: Play) -> Result<GameStatus, ManagerError> {
+        let managed = self.games.get_mut(&id).ok_or(NoSuchGame)?;
+        let side = managed.game.state.side_to_play;
+        let status = managed.game.do_play(play)?;
+        if let Some(clock) = &mut managed.clock {
+            clock.add_increment(side);
+            managed.game.emit(GameUpdate::ClockUpdated { side, remaining: clock.remaining(side) });
+        }
+        let record = managed.game.play_history.last()
+            .expect("Play history should not be empty after a play.")
+            .clone();
+        self.emit(GameEvent::Played { id, record });
+        if let Over(outcome) = status {
+            self.emit(GameEvent::Ended { id, outcome });
+        }
+        Ok(status)
+    }
+
+    /// Advance the shared timer by the given amount of time, deducting it from the clock of the
+    /// side to play in each ongoing timed game. Any game in which the side to play runs out of time
+    /// is lost by that side. Returns the ids of any such games.
+    pub fn tick(&mut self, elapsed: Duration) -> Vec<GameId> {
+        let mut timed_out = vec![];
+        for (id, managed) in &mut self.games {
+            if managed.game.state.status != Ongoing {
+                continue
+            }
+            if let Some(clock) = &mut managed.clock {
+                let side = managed.game.state.side_to_play;
+                let flagged = clock.deduct(side, elapsed);
+                managed.game.emit(GameUpdate::ClockUpdated { side, remaining: clock.remaining(side) });
+                if flagged {
+                    let outcome = Win(Timeout, side.other());
+                    managed.game.end(outcome);
+                    timed_out.push((*id, outcome));
+                }
+            }
+        }
+        timed_out.sort_by_key(|(id, _)| *id);
+        for &(id, outcome) in &timed_out {
+            self.emit(GameEvent::Ended { id, outcome });
+        }
+        timed_out.into_iter().map(|(id, _)| id).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ManagerError;
+    use crate::error::PlayInvalid::GameOver;
+    use crate::game::clock::TimeControl;
+    use crate::game::manager::{GameEvent, GameManager};
+    use crate::game::GameOutcome::Win;
+    use crate::game::GameStatus::{Ongoing, Over};
+    use crate::game::WinReason::Timeout;
+    use crate::game::SmallBasicGame;
+    use crate::pieces::Side::Attacker;
+    use crate::play::Play;
+    use crate::preset::{boards, rules};
+    use crate::board::state::SmallBasicBoardState;
+    use std::sync::{Arc, Mutex};
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    #[test]
+    fn test_game_manager() {
+        let events = Arc::new(Mutex::new(vec![]));
+        let mut manager = GameManager::new();
+        let events_clone = Arc::clone(&events);
+        manager.add_listener(move |e: &GameEvent| events_clone.lock().unwrap().push(e.clone()));
+
+        let tc = TimeControl::new(Duration::from_secs(10), Duration::from_secs(1));
+        let untimed = manager.add_game(SmallBasicGame::new(rules::BRANDUBH, boards::BRANDUBH).unwrap(), None);
+        let timed = manager.add_game(SmallBasicGame::new(rules::BRANDUBH, boards::BRANDUBH).unwrap(), Some(tc));
+        assert_eq!(manager.len(), 2);
+
+        let play = Play::from_str("d1-c1").unwrap();
+        assert_eq!(manager.do_play(timed, play), Ok(Ongoing));
+        assert_eq!(manager.get(timed).unwrap().clock.unwrap().remaining(Attacker), Duration::from_secs(11));
+        assert_eq!(manager.get(untimed).unwrap().game.play_history.len(), 0);
+        assert_eq!(manager.do_play(99, play), Err(ManagerError::NoSuchGame));
+
+        assert!(manager.tick(Duration::from_secs(5)).is_empty());
+        assert_eq!(manager.tick(Duration::from_secs(5)), vec![timed]);
+        assert_eq!(manager.get(timed).unwrap().game.state.status, Over(Win(Timeout, Attacker)));
+        assert_eq!(manager.get(untimed).unwrap().game.state.status, Ongoing);
+        assert_eq!(
+            manager.do_play(timed, Play::from_str("d3-c3").unwrap()),
+            Err(ManagerError::InvalidPlay(GameOver))
+        );
+        assert!(manager.remove_game(untimed).is_some());
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 5);
+        assert_eq!(events[0], GameEvent::Created { id: untimed });
+        assert!(matches!(events[2], GameEvent::Played { id, .. } if id == timed));
+        assert_eq!(events[3], GameEvent::Ended { id: timed, outcome: Win(Timeout, Attacker) });
+        assert_eq!(events[4], GameEvent::Removed { id: untimed });
+    }
+
+    #[test]
+    fn test_game_manager_is_send() {
+        fn is_send<T: Send>() {}
+        is_send::<GameManager<SmallBasicBoardState>>();
+    }
+}
@@ -1,16 +1,31 @@
+pub mod builder;
+pub mod cache;
+pub mod clock;
+pub mod controller;
+pub mod events;
+pub mod history;
+pub mod identity;
 pub mod logic;
+pub mod manager;
+pub mod movegen;
+pub mod series;
 pub mod state;
 
 use crate::board::state::{BoardState, HugeBasicBoardState, LargeBasicBoardState, MediumBasicBoardState, SmallBasicBoardState};
-use crate::error::{BoardError, PlayInvalid, ParseError};
+use crate::error::{BoardError, PlayInvalid, ParseError, RulesError};
+use crate::game::builder::GameBuilder;
+use crate::game::cache::PlayCache;
+use crate::game::events::{GameUpdate, Subscribers};
+use crate::game::history::{HistoryEntry, StateFlags};
 use crate::game::logic::GameLogic;
-use crate::game::state::GameState;
-use crate::pieces::{PlacedPiece, Side};
-use crate::play::{Play, PlayRecord, ValidPlayIterator};
+use crate::game::state::{GameState, RepetitionStatus};
+use crate::pieces::{Piece, PieceType, PlacedPiece, Side};
+use crate::play::{Play, PlayRecord, ValidPlay, ValidPlayIterator};
 use crate::rules::Ruleset;
-use crate::tiles::Tile;
+use crate::tiles::{Tile, TileSet};
+use crate::utils::InlineVec;
 use std::cmp::PartialEq;
-use std::collections::HashSet;
+use std::sync::mpsc::Receiver;
 
 /// The reason why a game has been won.
 #[derive(Eq, PartialEq, Debug, Copy, Clone, Hash)]
@@ -19,17 +34,32 @@ pub enum WinReason {
     KingEscaped,
     /// King has escaped through an exit fort.
     ExitFort,
+    /// King has reached the target tile specified by the rules.
+    KingReachedTarget,
     /// King has been captured.
     KingCaptured,
-    /// All the other side's pieces have been captured.
+    /// Attacker has occupied the throne.
+    ThroneOccupied,
+    /// All the other side's pieces have been captured (or, if the rules so provide, all the
+    /// defender's pieces other than the king).
     AllCaptured,
     /// The other side is completely enclosed (with or without edge or corner access, depending on
     /// the rules).
     Enclosed,
     /// The other side has no legal plays available.
     NoPlays,
+    /// The winning side has captured the number of enemy pieces specified by the rules.
+    CaptureCount,
     /// The other side has repeated a move too many times.
-    Repetition
+    Repetition,
+    /// The other side has run out of time.
+    Timeout,
+    /// The move limit specified by the rules has been reached and the winning side is ahead on
+    /// material.
+    MoveLimit,
+    /// The game was stopped early and awarded to the winning side, for example by an
+    /// [`engine::runner`](crate::engine::runner) because both engines agreed on the result.
+    Adjudicated
 }
 
 /// The reason why a game has been drawn.
@@ -38,7 +68,12 @@ pub enum DrawReason {
     /// A move has been repeated too many times.
     Repetition,
     /// Player has no legal plays available.
-    NoPlays
+    NoPlays,
+    /// The move limit specified by the rules has been reached.
+    MoveLimit,
+    /// The game was stopped early and declared drawn, for example by an
+    /// [`engine::runner`](crate::engine::runner) because the position was dead equal.
+    Adjudicated
 }
 
 /// The outcome of a single game.
@@ -50,12 +85,21 @@ pub enum GameOutcome {
     Draw(DrawReason)
 }
 
+/// The number of captured pieces stored inline in [`Captures`]. A custodial capture can take at
+/// most three pieces, so only large shieldwall captures need to allocate.
+pub const INLINE_CAPTURES: usize = 4;
+
+/// The pieces captured by a single play, stored inline to avoid a heap allocation for most plays.
+pub type Captures = InlineVec<PlacedPiece, INLINE_CAPTURES>;
+
 /// The effects of a single play, including captures and the game outcome caused by the play, if
 /// any.
 #[derive(Eq, PartialEq, Debug, Default, Clone)]
 pub struct PlayEffects {
     /// Tiles containing pieces that have been captured by the move.
-    pub captures: HashSet<PlacedPiece>,
+    pub captures: Captures,
+    /// The piece into which the moving piece was promoted, if the play resulted in a promotion.
+    pub promotion: Option<Piece>,
     /// The outcome of the game, if the move has brought the game to an end.
     pub game_outcome: Option<GameOutcome>
 }
@@ -69,41 +113,229 @@ pub enum GameStatus {
     Over(GameOutcome)
 }
 
+/// A summary of a finished game, for display on a result screen or storage in a database.
+#[derive(Eq, PartialEq, Debug, Copy, Clone, Hash)]
+pub struct GameSummary {
+    /// The outcome of the game.
+    pub outcome: GameOutcome,
+    /// The number of plays made in the game (by either side).
+    pub plays: usize,
+    /// The number of defending pieces captured by the attacker.
+    pub attacker_captures: u16,
+    /// The number of attacking pieces captured by the defender.
+    pub defender_captures: u16,
+    /// A hash of the final position (see [`GameState::position_hash`]).
+    pub position_hash: u64
+}
+
+impl GameSummary {
+
+    /// Summarise the game which ended in the given state, or return `None` if the game has not
+    /// ended.
+    pub fn from_state<T: BoardState>(state: &GameState<T>) -> Option<Self> {
+        match state.status {
+            GameStatus::Over(outcome) => Some(Self {
+                outcome,
+                plays: state.turn,
+                attacker_captures: state.attacker_captures,
+                defender_captures: state.defender_captures,
+                position_hash: state.position_hash()
+            }),
+            GameStatus::Ongoing => None
+        }
+    }
+}
+
 /// A struct representing a single game, including all state and associated information (such as
-/// rules) needed to play. This struct also keeps a record of all previous plays and the game state
-/// after each turn (to allow undoing plays).
+/// rules) needed to play. This struct also keeps a record of all previous plays, and the
+/// information needed to undo each of them (from which any previous state of the game can be
+/// reconstructed).
 #[derive(Clone)]
 pub struct Game<T: BoardState> {
     pub logic: GameLogic,
     pub state: GameState<T>,
     pub play_history: Vec<PlayRecord>,
-    pub state_history: Vec<GameState<T>>
+    /// The information needed to undo each play (or swap of sides) made so far.
+    pub history: Vec<HistoryEntry>,
+    subscribers: Subscribers,
+    play_cache: Option<PlayCache>
 }
 
 impl<T: BoardState> Game<T> {
 
-    /// Create a new [`Game`] from the given rules and starting positions.
+    /// Create a [`GameBuilder`] to configure and create a new game.
+    pub fn builder() -> GameBuilder<T> {
+        GameBuilder::new()
+    }
+
+    /// Create a new [`Game`] from the given rules and starting positions. Returns an error if the
+    /// starting position cannot be parsed, or [`ParseError::BadRules`] if the rules promote pieces
+    /// to a type which the board state cannot store (or to a king).
     pub fn new(rules: Ruleset, starting_board: &str) -> Result<Self, ParseError> {
+        for p in rules.promotions {
+            if p.promote_to == PieceType::King {
+                return Err(RulesError::PromotionToKing.into())
+            }
+            if !T::supports_piece_type(p.promote_to) {
+                return Err(RulesError::UnsupportedPromotion(p.promote_to).into())
+            }
+        }
         let state: GameState<T> = GameState::new(starting_board, rules.starting_side)?;
         let logic = GameLogic::new(rules, state.board.side_len());
             
-        Ok(Self { state, logic, play_history: vec![], state_history: vec![state] })
+        Ok(Self {
+            state,
+            logic,
+            play_history: vec![],
+            history: vec![],
+            subscribers: Subscribers::default(),
+            play_cache: None
+        })
     }
     
     /// Actually "do" a play, checking validity, getting outcome, applying outcome to board state,
     /// switching side to play and returning a description of the game status following the move.
     pub fn do_play(&mut self, play: Play) -> Result<GameStatus, PlayInvalid> {
         let (state, play_record) = self.logic.do_play(play, self.state)?.into();
-        self.state_history.push(self.state);
+        if let Some(cache) = &mut self.play_cache {
+            cache.update(&self.logic, &self.state, &play_record, &state);
+        }
+        let moved = self.state.board.get_piece(play.from).expect("Valid play must move a piece.");
+        self.history.push(HistoryEntry::Play { moved, flags: StateFlags::of(&self.state) });
         self.state = state;
+        if !self.subscribers.is_empty() {
+            self.emit(GameUpdate::PlayApplied(play_record.clone()));
+            if !play_record.effects.captures.is_empty() {
+                self.emit(GameUpdate::PiecesCaptured(play_record.effects.captures.clone()));
+            }
+        }
         self.play_history.push(play_record);
+        if let Some(summary) = self.summary() {
+            self.emit(GameUpdate::GameOver(summary));
+        }
         Ok(self.state.status)
     }
+
+    /// Subscribe to updates about the game. Returns a receiver which yields a [`GameUpdate`] for
+    /// each play made, each set of pieces captured and the end of the game (and each clock update,
+    /// if the game is owned by a [`GameManager`](manager::GameManager)). This is an alternative to
+    /// the listener callbacks of `GameManager`, suitable for feeding UI event loops or other
+    /// threads.
+    ///
+    /// Subscriptions are not copied when a game is cloned.
+    pub fn events(&mut self) -> Receiver<GameUpdate> {
+        self.subscribers.subscribe()
+    }
+
+    /// Send the given update to all subscribers.
+    pub(crate) fn emit(&mut self, update: GameUpdate) {
+        self.subscribers.send(update);
+    }
+
+    /// End the game with the given outcome, other than as a result of a play (for example, on
+    /// timeout), and notify subscribers.
+    pub(crate) fn end(&mut self, outcome: GameOutcome) {
+        self.state.status = GameStatus::Over(outcome);
+        if let Some(cache) = &mut self.play_cache {
+            cache.rebuild(&self.logic, &self.state);
+        }
+        if let Some(summary) = self.summary() {
+            self.emit(GameUpdate::GameOver(summary));
+        }
+    }
     
+    /// Swap sides under the pie rule, instead of making a play. This is only permitted if the rules
+    /// provide for it and exactly one play has been made. The board and side to play are
+    /// unchanged, but the player who made the first play will now play the other side.
+    pub fn swap_sides(&mut self) -> Result<(), PlayInvalid> {
+        if self.state.status != GameStatus::Ongoing {
+            return Err(PlayInvalid::GameOver)
+        }
+        if !self.logic.rules.swap_rule || self.state.turn != 1 || self.state.sides_swapped {
+            return Err(PlayInvalid::SwapNotPermitted)
+        }
+        self.history.push(HistoryEntry::Swap);
+        self.state.sides_swapped = true;
+        Ok(())
+    }
+
+    /// A summary of the game, or `None` if the game is still ongoing.
+    pub fn summary(&self) -> Option<GameSummary> {
+        GameSummary::from_state(&self.state)
+    }
+
+    /// Undo the last play (or swap of sides under the pie rule), and notify subscribers.
     pub fn undo_last_play(&mut self) {
-        if let Some(state) = self.state_history.pop() {
-            self.state = state;
-            self.play_history.pop();
+        if let Some(entry) = self.history.pop() {
+            let record = match entry {
+                HistoryEntry::Play { .. } => self.play_history.pop(),
+                HistoryEntry::Swap => None
+            };
+            entry.reverse(&mut self.state, record.as_ref());
+            if let Some(cache) = &mut self.play_cache {
+                cache.rebuild(&self.logic, &self.state);
+            }
+            self.emit(GameUpdate::Undone);
+        }
+    }
+
+    /// Reconstruct the state of the game after the first `n` entries in its history (ie, plays or
+    /// swaps of sides), or return `None` if there are fewer than `n` entries. In particular,
+    /// `self.state_at(0)` is the state at the start of the game.
+    pub fn state_at(&self, n: usize) -> Option<GameState<T>> {
+        if n > self.history.len() {
+            return None
+        }
+        let mut state = self.state;
+        let mut records = self.play_history.iter().rev();
+        for entry in self.history[n..].iter().rev() {
+            let record = match entry {
+                HistoryEntry::Play { .. } => records.next(),
+                HistoryEntry::Swap => None
+            };
+            entry.reverse(&mut state, record);
+        }
+        Some(state)
+    }
+
+    /// How the given play would affect the repetition count of the side to play. See
+    /// [`GameLogic::repetition_status`].
+    pub fn repetition_status(&self, play: Play) -> Result<Option<RepetitionStatus>, PlayInvalid> {
+        if self.state.status != GameStatus::Ongoing {
+            return Err(PlayInvalid::GameOver)
+        }
+        self.logic.repetition_status(play, &self.state)
+    }
+
+    /// Keep a cache of the valid plays in the current position, which is updated incrementally
+    /// after each play (see [`PlayCache`]). This makes [`Self::valid_plays`] much cheaper.
+    pub fn enable_play_cache(&mut self) {
+        self.play_cache = Some(PlayCache::new(&self.logic, &self.state));
+    }
+
+    /// Get every valid play that can be made by the side to play, using the play cache if it has
+    /// been enabled. Plays are returned in a deterministic order.
+    pub fn valid_plays(&self) -> Vec<ValidPlay> {
+        match &self.play_cache {
+            Some(cache) => cache.valid_plays(&self.state),
+            None => self.logic.valid_plays(&self.state)
+        }
+    }
+
+    /// Every tile to which the piece at the given tile can legally move. The set is empty if there
+    /// is no piece at the tile, or the piece cannot move (including because it belongs to the side
+    /// not to play). Uses the play cache, if it has been enabled.
+    pub fn destinations_from(&self, tile: Tile) -> TileSet {
+        let to_play = self.state.side_to_play;
+        if self.state.status != GameStatus::Ongoing
+            || self.state.board.get_piece(tile).is_none_or(|p| p.side != to_play) {
+            return TileSet::new()
+        }
+        match &self.play_cache {
+            Some(cache) => cache.plays_from(tile).iter().map(|p| p.play.to()).collect(),
+            None => self.iter_plays(tile)
+                .map(|iter| iter.map(|p| p.play.to()).collect())
+                .unwrap_or_default()
         }
     }
 
@@ -127,11 +359,17 @@ pub type HugeBasicGame = Game<HugeBasicBoardState>;
 #[cfg(test)]
 mod tests {
     use crate::board::state::SmallBasicBoardState;
+    use crate::error::PlayInvalid;
     use crate::game::Game;
+    use crate::game::GameOutcome::Win;
+    use crate::game::WinReason::KingEscaped;
+    use crate::pieces::Side::Defender;
     use crate::play::Play;
     use crate::preset::{boards, rules};
-    use crate::tiles::Tile;
+    use crate::rules::Ruleset;
+    use crate::tiles::{Tile, TileSet};
     use std::collections::HashSet;
+    use std::str::FromStr;
 
     #[test]
     fn test_iter_plays() {
@@ -206,6 +444,31 @@ mod tests {
         )
     }
     
+    #[test]
+    fn test_swap_sides() {
+        let rules = Ruleset { swap_rule: true, ..rules::BRANDUBH };
+        let mut g: Game<SmallBasicBoardState> = Game::new(rules, boards::BRANDUBH).unwrap();
+        assert_eq!(g.swap_sides(), Err(PlayInvalid::SwapNotPermitted));
+        g.do_play(Play::from_tiles(Tile::new(0, 3), Tile::new(0, 2)).unwrap()).unwrap();
+        let state_1 = g.state;
+        assert!(g.swap_sides().is_ok());
+        assert!(g.state.sides_swapped);
+        assert_eq!(g.state.side_to_play, Defender);
+        assert_eq!(g.swap_sides(), Err(PlayInvalid::SwapNotPermitted));
+        g.do_play(Play::from_tiles(Tile::new(2, 3), Tile::new(2, 1)).unwrap()).unwrap();
+        assert!(g.state.sides_swapped);
+        assert_eq!(g.play_history.len(), 2);
+        g.undo_last_play();
+        assert!(g.state.sides_swapped);
+        g.undo_last_play();
+        assert_eq!(g.state, state_1);
+        assert_eq!(g.play_history.len(), 1);
+
+        let mut g: Game<SmallBasicBoardState> = Game::new(rules::BRANDUBH, boards::BRANDUBH).unwrap();
+        g.do_play(Play::from_tiles(Tile::new(0, 3), Tile::new(0, 2)).unwrap()).unwrap();
+        assert_eq!(g.swap_sides(), Err(PlayInvalid::SwapNotPermitted));
+    }
+
     #[test]
     fn test_undo() {
         let mut g: Game<SmallBasicBoardState> = Game::new(rules::BRANDUBH, boards::BRANDUBH).unwrap();
@@ -229,6 +492,38 @@ mod tests {
         assert_eq!(g.state, state_0);
 
     }
+
+    #[test]
+    fn test_destinations_from() {
+        let mut g: Game<SmallBasicBoardState> = Game::new(rules::BRANDUBH, boards::BRANDUBH).unwrap();
+        let tiles = |s: &[&str]| s.iter().map(|t| Tile::from_str(t).unwrap()).collect::<TileSet>();
+        let d1 = Tile::from_str("d1").unwrap();
+        assert_eq!(g.destinations_from(d1), tiles(&["b1", "c1", "e1", "f1"]));
+        assert_eq!(g.destinations_from(Tile::from_str("d2").unwrap()).len(), 6);
+        assert!(g.destinations_from(Tile::from_str("d3").unwrap()).is_empty());
+        assert!(g.destinations_from(Tile::from_str("a1").unwrap()).is_empty());
+        g.enable_play_cache();
+        assert_eq!(g.destinations_from(d1), tiles(&["b1", "c1", "e1", "f1"]));
+        g.do_play(Play::from_str("d1-b1").unwrap()).unwrap();
+        assert!(g.destinations_from(Tile::from_str("b1").unwrap()).is_empty());
+        assert_eq!(g.destinations_from(Tile::from_str("d3").unwrap()), tiles(&["c3", "b3", "a3", "e3", "f3", "g3"]));
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut g: Game<SmallBasicBoardState> = Game::new(rules::BRANDUBH, "7/1K5/7/7/7/1t5/5t1").unwrap();
+        assert!(g.summary().is_none());
+        for p in ["f7-f6", "b2-b1", "b6-b4", "b1-a1"] {
+            g.do_play(Play::from_str(p).unwrap()).unwrap();
+        }
+        let summary = g.summary().unwrap();
+        assert_eq!(summary.outcome, Win(KingEscaped, Defender));
+        assert_eq!(summary.plays, 4);
+        assert_eq!(summary.attacker_captures, 0);
+        assert_eq!(summary.position_hash, g.state.position_hash());
+        let other: Game<SmallBasicBoardState> = Game::new(rules::BRANDUBH, "K6/7/7/1t5/7/7/5t1").unwrap();
+        assert_ne!(summary.position_hash, other.state.position_hash());
+    }
     
 
 }
\ No newline at end of file
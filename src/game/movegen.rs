@@ -0,0 +1,161 @@
+use crate::board::state::BoardState;
+use crate::game::logic::GameLogic;
+use crate::game::state::GameState;
+use crate::game::GameOutcome::Win;
+use crate::game::GameStatus::Over;
+use crate::pieces::PieceType::King;
+use crate::play::ValidPlay;
+use std::collections::VecDeque;
+
+/// The stages in which plays are generated by a [`StagedPlayIterator`], in order.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PlayStage {
+    /// Plays by the king which win the game (by escaping or otherwise).
+    KingEscape,
+    /// Other plays by the king.
+    King,
+    /// Plays by other pieces which capture at least one enemy piece.
+    Capture,
+    /// All other plays.
+    Quiet
+}
+
+/// A valid play together with the stage in which it was generated.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct StagedPlay {
+    pub stage: PlayStage,
+    pub play: ValidPlay
+}
+
+/// An iterator over all valid plays for the side to play, generated in stages (see
+/// [`PlayStage`]) so that the plays most likely to be good are returned first. This gives a
+/// reasonable ordering for alpha-beta search, and allows a user interface to group plays.
+///
+/// Each stage is only generated when the previous stage has been exhausted, so a search which
+/// stops early (for example, on finding a winning play) does no unnecessary work. Within each
+/// stage, plays are returned in a deterministic order.
+pub struct StagedPlayIterator<'logic, 'state, T: BoardState> {
+    logic: &'logic GameLogic,
+    state: &'state GameState<T>,
+    stage: Option<PlayStage>,
+    buffer: VecDeque<ValidPlay>,
+    king_rest: Vec<ValidPlay>,
+    quiet: Vec<ValidPlay>
+}
+
+impl<'logic, 'state, T: BoardState> StagedPlayIterator<'logic, 'state, T> {
+
+    pub fn new(logic: &'logic GameLogic, state: &'state GameState<T>) -> Self {
+        let mut iter = Self {
+            logic,
+            state,
+            stage: None,
+            buffer: VecDeque::new(),
+            king_rest: vec![],
+            quiet: vec![]
+        };
+        iter.fill(PlayStage::KingEscape);
+        iter
+    }
+
+    /// Fill the buffer with the plays of the given stage. Plays belonging to a later stage which
+    /// are found while doing so are kept until that stage is reached.
+    fn fill(&mut self, stage: PlayStage) {
+        self.stage = Some(stage);
+        let logic = self.logic;
+        let state = self.state;
+        let king_tile = state.board.get_king();
+        let king_to_play = state.board.get_piece(king_tile)
+            .is_some_and(|p| p.piece_type == King && p.side == state.side_to_play);
+        match stage {
+            PlayStage::KingEscape => if king_to_play {
+                for p in logic.iter_plays(king_tile, state).expect("King tile must be occupied.") {
+                    let wins = matches!(
+                        logic.do_valid_play(p, *state).new_state.status,
+                        Over(Win(_, side)) if side == state.side_to_play
+                    );
+                    if wins {
+                        self.buffer.push_back(p);
+                    } else {
+                        self.king_rest.push(p);
+                    }
+                }
+            },
+            PlayStage::King => self.buffer.extend(self.king_rest.drain(..)),
+            PlayStage::Capture => {
+                let others = state.board.iter_occupied(state.side_to_play)
+                    .filter(|t| !(king_to_play && *t == king_tile))
+                    .flat_map(|t| logic.iter_plays(t, state).expect("Tile must be occupied."));
+                for p in others {
+                    let mut after = *state;
+                    let piece = after.board.move_piece(p.play.from, p.play.to());
+                    if logic.get_captures(p.play, piece, &after).is_empty() {
+                        self.quiet.push(p);
+                    } else {
+                        self.buffer.push_back(p);
+                    }
+                }
+            },
+            PlayStage::Quiet => self.buffer.extend(self.quiet.drain(..))
+        }
+    }
+}
+
+impl<T: BoardState> Iterator for StagedPlayIterator<'_, '_, T> {
+    type Item = StagedPlay;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let stage = self.stage?;
+            if let Some(play) = self.buffer.pop_front() {
+                return Some(StagedPlay { stage, play })
+            }
+            match stage {
+                PlayStage::KingEscape => self.fill(PlayStage::King),
+                PlayStage::King => self.fill(PlayStage::Capture),
+                PlayStage::Capture => self.fill(PlayStage::Quiet),
+                PlayStage::Quiet => self.stage = None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::logic::GameLogic;
+    use crate::game::movegen::PlayStage::{Capture, King, KingEscape, Quiet};
+    use crate::game::movegen::{PlayStage, StagedPlay};
+    use crate::game::state::SmallBasicGameState;
+    use crate::pieces::Side::{Attacker, Defender};
+    use crate::play::Play;
+    use crate::preset::rules;
+    use std::collections::HashSet;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_staged_plays() {
+        let logic = GameLogic::new(rules::BRANDUBH, 7);
+        let state = SmallBasicGameState::new("1K5/1T5/1t5/4T2/7/7/7", Defender).unwrap();
+        let staged: Vec<StagedPlay> = logic.staged_plays(&state).collect();
+        let stages: Vec<PlayStage> = staged.iter().map(|s| s.stage).collect();
+        assert!(stages.is_sorted());
+        let escapes: HashSet<Play> = staged.iter()
+            .filter(|s| s.stage == KingEscape)
+            .map(|s| s.play.play)
+            .collect();
+        assert_eq!(escapes, ["b1-a1", "b1-g1"].iter().map(|p| Play::from_str(p).unwrap()).collect());
+        assert!(stages.contains(&King));
+        let captures: Vec<Play> = staged.iter()
+            .filter(|s| s.stage == Capture)
+            .map(|s| s.play.play)
+            .collect();
+        assert_eq!(captures, vec![Play::from_str("e4-b4").unwrap()]);
+        assert!(stages.contains(&Quiet));
+        let all: HashSet<Play> = staged.iter().map(|s| s.play.play).collect();
+        assert_eq!(all.len(), staged.len());
+        assert_eq!(all, logic.valid_plays(&state).iter().map(|p| p.play).collect());
+
+        let state = SmallBasicGameState { side_to_play: Attacker, ..state };
+        assert!(logic.staged_plays(&state).all(|s| s.stage >= Capture));
+    }
+}
@@ -0,0 +1,271 @@
+use crate::board::state::BoardState;
+use crate::error::{MatchError, PlayInvalid};
+use crate::game::GameOutcome::{Draw, Win};
+use crate::game::GameStatus::{Ongoing, Over};
+use crate::game::{Game, GameOutcome, GameStatus};
+use crate::pieces::Side;
+use crate::pieces::Side::{Attacker, Defender};
+use crate::play::{Play, PlayRecord};
+use crate::rules::Ruleset;
+
+/// One of the two players in a [`Match`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Player {
+    /// The player who plays as attacker in the first game.
+    First,
+    /// The player who plays as defender in the first game.
+    Second
+}
+
+impl Player {
+
+    /// Return the other player.
+    pub fn other(&self) -> Self {
+        match self {
+            Player::First => Player::Second,
+            Player::Second => Player::First
+        }
+    }
+}
+
+/// The result of a completed [`Match`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MatchResult {
+    /// The match was won by the given player.
+    Won(Player),
+    /// The match was drawn.
+    Drawn
+}
+
+/// A record of a single game within a [`Match`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchGameRecord {
+    /// The player who played as attacker.
+    pub attacker: Player,
+    /// The outcome of the game, if it has finished.
+    pub outcome: Option<GameOutcome>,
+    /// The plays made in the game.
+    pub plays: Vec<PlayRecord>
+}
+
+impl MatchGameRecord {
+
+    /// The player who won the game, if any.
+    pub fn winner(&self) -> Option<Player> {
+        match self.outcome {
+            Some(Win(_, side)) => Some(self.player(side)),
+            _ => None
+        }
+    }
+
+    /// The player who played as the given side.
+    pub fn player(&self, side: Side) -> Player {
+        match side {
+            Attacker => self.attacker,
+            Defender => self.attacker.other()
+        }
+    }
+}
+
+/// A series of up to `n_games` games between two players, who alternate sides from game to game
+/// (the first player attacks in the first game). A win is worth one point and a draw half a point
+/// to each player. The match ends early once one player cannot be caught.
+///
+/// Because attacker and defender are rarely equally matched, it is common for each player to win
+/// the games in which they play the stronger side. If the players finish level on points, the
+/// match is therefore won by the player whose wins took fewer plays in total, and is only drawn if
+/// that is also equal.
+#[derive(Clone)]
+pub struct Match<T: BoardState> {
+    rules: Ruleset,
+    starting_board: String,
+    n_games: usize,
+    games: Vec<Game<T>>
+}
+
+impl<T: BoardState> Match<T> {
+
+    /// Create a new match of up to `n_games` games, each played using the given rules and
+    /// starting position. The first game is created immediately. Returns an error if `n_games` is
+    /// zero or the starting position cannot be parsed.
+    pub fn new(rules: Ruleset, starting_board: &str, n_games: usize) -> Result<Self, MatchError> {
+        if n_games == 0 {
+            return Err(MatchError::NoGames)
+        }
+        let first = Game::new(rules, starting_board).map_err(MatchError::BadPosition)?;
+        Ok(Self { rules, starting_board: starting_board.to_string(), n_games, games: vec![first] })
+    }
+
+    /// The player who plays (or played) as attacker in the game with the given index, taking
+    /// account of any swap of sides under the pie rule.
+    pub fn attacker(&self, game_index: usize) -> Player {
+        let default = if game_index.is_multiple_of(2) { Player::First } else { Player::Second };
+        match self.games.get(game_index) {
+            Some(g) if g.state.sides_swapped => default.other(),
+            _ => default
+        }
+    }
+
+    /// The game currently being played, or `None` if the match is over.
+    pub fn current_game(&self) -> Option<&Game<T>> {
+        self.games.last().filter(|g| g.state.status == Ongoing)
+    }
+
+    /// A mutable reference to the game currently being played, or `None` if the match is over.
+    pub fn current_game_mut(&mut self) -> Option<&mut Game<T>> {
+        self.games.last_mut().filter(|g| g.state.status == Ongoing)
+    }
+
+    /// The player whose turn it is to play, or `None` if the match is over.
+    pub fn player_to_play(&self) -> Option<Player> {
+        let game = self.current_game()?;
+        let attacker = self.attacker(self.games.len() - 1);
+        Some(if game.state.side_to_play == Attacker { attacker } else { attacker.other() })
+    }
+
+    /// Make a play in the current game. If the play ends the game and the match is not yet
+    /// decided, the next game is started.
+    pub fn do_play(&mut self, play: Play) -> Result<GameStatus, PlayInvalid> {
+        let game = self.current_game_mut().ok_or(PlayInvalid::GameOver)?;
+        let status = game.do_play(play)?;
+        if status != Ongoing && !self.is_decided() {
+            let next = Game::new(self.rules, &self.starting_board)
+                .expect("Starting board was already parsed successfully.");
+            self.games.push(next);
+        }
+        Ok(status)
+    }
+
+    /// Records of each game played (or being played) so far.
+    pub fn records(&self) -> Vec<MatchGameRecord> {
+        self.games.iter().enumerate().map(|(i, g)| MatchGameRecord {
+            attacker: self.attacker(i),
+            outcome: match g.state.status {
+                Over(outcome) => Some(outcome),
+                Ongoing => None
+            },
+            plays: g.play_history.clone()
+        }).collect()
+    }
+
+    /// The player who won the game with the given index, if any.
+    fn winner(&self, game_index: usize) -> Option<Player> {
+        match self.games[game_index].state.status {
+            Over(Win(_, Attacker)) => Some(self.attacker(game_index)),
+            Over(Win(_, Defender)) => Some(self.attacker(game_index).other()),
+            _ => None
+        }
+    }
+
+    /// The number of half-points scored by the given player in completed games.
+    fn half_points(&self, player: Player) -> usize {
+        self.games.iter().enumerate().map(|(i, g)| match g.state.status {
+            Over(Win(..)) if self.winner(i) == Some(player) => 2,
+            Over(Draw(_)) => 1,
+            _ => 0
+        }).sum()
+    }
+
+    /// The number of points scored by the given player in completed games.
+    pub fn score(&self, player: Player) -> f64 {
+        self.half_points(player) as f64 / 2.0
+    }
+
+    /// The number of games that have been completed.
+    pub fn games_completed(&self) -> usize {
+        self.games.iter().filter(|g| g.state.status != Ongoing).count()
+    }
+
+    /// Whether the result of the match has been determined, either because all games have been
+    /// played or because one player has an unassailable lead.
+    pub fn is_decided(&self) -> bool {
+        let remaining = self.n_games.saturating_sub(self.games_completed());
+        self.half_points(Player::First).abs_diff(self.half_points(Player::Second)) > remaining * 2
+            || remaining == 0
+    }
+
+    /// The result of the match, or `None` if it has not yet been decided.
+    pub fn result(&self) -> Option<MatchResult> {
+        if !self.is_decided() {
+            return None
+        }
+        let first = self.half_points(Player::First);
+        let second = self.half_points(Player::Second);
+        if first != second {
+            return Some(MatchResult::Won(if first > second { Player::First } else { Player::Second }))
+        }
+        // Tie-break on total length of games won.
+        let win_plays = |player: Player| -> usize {
+            self.games.iter().enumerate()
+                .filter(|(i, _)| self.winner(*i) == Some(player))
+                .map(|(_, g)| g.play_history.len())
+                .sum()
+        };
+        let first = win_plays(Player::First);
+        let second = win_plays(Player::Second);
+        Some(if first == second {
+            MatchResult::Drawn
+        } else {
+            MatchResult::Won(if first < second { Player::First } else { Player::Second })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::state::SmallBasicBoardState;
+    use crate::error::MatchError;
+    use crate::game::series::{Match, MatchResult, Player};
+    use crate::game::GameOutcome::Win;
+    use crate::game::WinReason::KingEscaped;
+    use crate::pieces::Side::Defender;
+    use crate::play::Play;
+    use crate::preset::rules;
+    use std::str::FromStr;
+
+    fn play_all(m: &mut Match<SmallBasicBoardState>, plays: &[&str]) {
+        for p in plays {
+            m.do_play(Play::from_str(p).unwrap()).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_match() {
+        let board = "7/1K5/7/7/7/7/5t1";
+        let slow_escape = ["f7-f6", "b2-c2", "f6-f5", "c2-c1", "f5-f4", "c1-a1"];
+        let fast_escape = ["f7-f6", "b2-b1", "f6-f5", "b1-a1"];
+
+        let mut m: Match<SmallBasicBoardState> = Match::new(rules::BRANDUBH, board, 2).unwrap();
+        assert_eq!(m.player_to_play(), Some(Player::First));
+        play_all(&mut m, &slow_escape);
+        assert_eq!(m.score(Player::Second), 1.0);
+        assert_eq!(m.result(), None);
+        assert_eq!(m.player_to_play(), Some(Player::Second));
+        play_all(&mut m, &fast_escape);
+        assert_eq!(m.score(Player::First), 1.0);
+        assert!(m.current_game().is_none());
+        // Level on points, but the first player won more quickly.
+        assert_eq!(m.result(), Some(MatchResult::Won(Player::First)));
+        let records = m.records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].attacker, Player::First);
+        assert_eq!(records[0].outcome, Some(Win(KingEscaped, Defender)));
+        assert_eq!(records[1].plays.len(), 4);
+
+        // Three game match, so each player only plays the same side twice.
+        let mut m: Match<SmallBasicBoardState> = Match::new(rules::BRANDUBH, board, 3).unwrap();
+        play_all(&mut m, &fast_escape);
+        assert_eq!(m.result(), None);
+        play_all(&mut m, &slow_escape);
+        assert_eq!(m.score(Player::Second), 1.0);
+        assert_eq!(m.score(Player::First), 1.0);
+        assert_eq!(m.result(), None);
+        play_all(&mut m, &fast_escape);
+        assert_eq!(m.result(), Some(MatchResult::Won(Player::Second)));
+
+        assert!(matches!(
+            Match::<SmallBasicBoardState>::new(rules::BRANDUBH, board, 0),
+            Err(MatchError::NoGames)
+        ));
+    }
+}
@@ -1,11 +1,12 @@
 use std::cmp::PartialEq;
-use crate::board::state::{BoardState, HugeBasicBoardState, LargeBasicBoardState, MediumBasicBoardState, SmallBasicBoardState};
+use crate::board::state::{BoardState, HugeBasicBoardState, HugeExtendedBoardState, LargeBasicBoardState, LargeExtendedBoardState, MediumBasicBoardState, MediumExtendedBoardState, SmallBasicBoardState, SmallExtendedBoardState};
 use crate::error::ParseError;
 use crate::game::GameStatus;
 use crate::game::GameStatus::Ongoing;
 use crate::pieces::Side;
 use crate::play::{Play, PlayRecord};
-use crate::utils::FixedSizeQueue;
+use crate::utils::{FixedSizeQueue, FnvHasher};
+use std::hash::Hasher;
 
 /// A short (fixed-size) record of the relevant information about a play we need to figure out
 /// if it is a repetition of a previous play.
@@ -113,6 +114,40 @@ impl RepetitionTracker {
     }
 }
 
+/// How a candidate play would affect the repetition count of the side making it, relative to the
+/// ruleset's [`RepetitionRule`](crate::rules::RepetitionRule). Returned by
+/// [`GameLogic::repetition_status`](crate::game::logic::GameLogic::repetition_status).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RepetitionStatus {
+    /// The side's repetition count before the play.
+    pub current: usize,
+    /// The side's repetition count after the play.
+    pub after: usize,
+    /// The repetition count at which the rule is triggered.
+    pub limit: usize,
+    /// Whether triggering the rule loses the game for the repeating side (otherwise it is drawn).
+    pub is_loss: bool
+}
+
+impl RepetitionStatus {
+
+    /// Whether the play would increase the side's repetition count.
+    pub fn increases(&self) -> bool {
+        self.after > self.current
+    }
+
+    /// The number of further repetitions the side could make after the play before the rule is
+    /// triggered.
+    pub fn remaining(&self) -> usize {
+        self.limit.saturating_sub(self.after + 1)
+    }
+
+    /// Whether the play would itself trigger the rule.
+    pub fn triggers(&self) -> bool {
+        self.after >= self.limit
+    }
+}
+
 /// This strict contains all state that can be used to evaluate play outcomes and board positions
 /// and that changes regularly. The idea is to keep this struct as small as possible to facilitate
 /// efficient play evaluation.
@@ -126,10 +161,17 @@ pub struct GameState<T: BoardState> {
     pub repetitions: RepetitionTracker,
     /// Number of plays since a piece was last captured.
     pub plays_since_capture: usize,
+    /// Number of defending pieces captured by the attacker so far.
+    pub attacker_captures: u16,
+    /// Number of attacking pieces captured by the defender so far.
+    pub defender_captures: u16,
     /// Current status of the game.
     pub status: GameStatus,
     /// Number of plays that have been taken by either side.
-    pub turn: usize
+    pub turn: usize,
+    /// Whether the players have swapped sides under the pie rule, so that the player who started
+    /// the game as one side is now playing the other.
+    pub sides_swapped: bool
 }
 
 impl <T: BoardState> GameState<T> {
@@ -139,10 +181,31 @@ impl <T: BoardState> GameState<T> {
             side_to_play,
             repetitions: RepetitionTracker::default(),
             plays_since_capture: 0,
+            attacker_captures: 0,
+            defender_captures: 0,
             status: Ongoing,
-            turn: 0
+            turn: 0,
+            sides_swapped: false
         })
     }
+
+    /// The number of enemy pieces captured so far by the given side.
+    pub fn captures_by(&self, side: Side) -> u16 {
+        match side {
+            Side::Attacker => self.attacker_captures,
+            Side::Defender => self.defender_captures
+        }
+    }
+
+    /// A hash of the position, ie, the placement of pieces on the board and the side to play. The
+    /// hash is the same on every platform, so it can be stored (for example, in a database of
+    /// games) and compared later.
+    pub fn position_hash(&self) -> u64 {
+        let mut hasher = FnvHasher::default();
+        hasher.write(self.board.to_fen().as_bytes());
+        hasher.write(&[self.side_to_play as u8]);
+        hasher.finish()
+    }
 }
 
 /// Game state supporting basic pieces (soldier and king), suitable for boards up to 7x7.
@@ -153,13 +216,30 @@ pub type MediumBasicGameState = GameState<MediumBasicBoardState>;
 pub type LargeBasicGameState = GameState<LargeBasicBoardState>;
 /// Game state supporting basic pieces (soldier and king), suitable for boards up to 21x21.
 pub type HugeBasicGameState = GameState<HugeBasicBoardState>;
+/// Game state supporting all piece types, suitable for boards up to 7x7.
+pub type SmallExtendedGameState = GameState<SmallExtendedBoardState>;
+/// Game state supporting all piece types, suitable for boards up to 11x11.
+pub type MediumExtendedGameState = GameState<MediumExtendedBoardState>;
+/// Game state supporting all piece types, suitable for boards up to 15x15.
+pub type LargeExtendedGameState = GameState<LargeExtendedBoardState>;
+/// Game state supporting all piece types, suitable for boards up to 21x21.
+pub type HugeExtendedGameState = GameState<HugeExtendedBoardState>;
 
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
-    use crate::game::state::RepetitionTracker;
+    use crate::game::state::{RepetitionTracker, SmallBasicGameState};
     use crate::pieces::Side;
     use crate::play::Play;
+    use crate::preset::boards;
+
+    #[test]
+    fn test_position_hash_stable() {
+        // The hash must be identical on every platform and in every version of the crate, so it is
+        // checked against a fixed value.
+        let state = SmallBasicGameState::new(boards::BRANDUBH, Side::Attacker).unwrap();
+        assert_eq!(state.position_hash(), 4319670239582499484);
+    }
 
     #[test]
     fn test_repetition_tracker() {
@@ -60,12 +60,46 @@
 //! 
 //! So if you just want to play a game on a 7x7 board, you can use a `SmallBasicGame` instead of a
 //! `Game<BitfieldBoardState<u64>>`.
+//!
+//! # Optional features
+//!
+//! - `tracing`: Emits [`tracing`](https://docs.rs/tracing) spans and events from play validation,
+//!   capture resolution and win condition checks, to help diagnose why a play was found to be
+//!   invalid or had a particular outcome.
+//! - `parallel`: Provides parallel versions of the batch operations in [`analysis::batch`], using
+//!   [`rayon`](https://docs.rs/rayon). The sequential versions are always available.
+//!
+//! # Determinism
+//!
+//! Given the same inputs, the crate's game logic produces the same results on every platform and
+//! target (including WASM), so that, for example, networked clients can check each other's
+//! results. In particular:
+//!
+//! - Play validation, captures and game outcomes use only integer arithmetic. Lists of plays and
+//!   of captured pieces are produced in a fixed order, never in the iteration order of a hash map
+//!   or set (which varies from run to run).
+//! - [`game::state::GameState::position_hash`] and the checksums of [`record::GameRecord`]s use
+//!   a fixed hash function ([`utils::FnvHasher`]) over a platform-independent encoding, rather than
+//!   the standard library's randomly seeded hasher.
+//! - Random playouts and setups draw from a seeded [`utils::SplitMix64`] generator, so the same
+//!   seed gives the same results, including in the parallel batch operations.
+//! - Positions can be evaluated deterministically using
+//!   [`analysis::model::OutcomeModel::score`], which computes a fixed-point score using only
+//!   integer arithmetic. [`game::controller::GameController::hint`] compares plays using this
+//!   score, breaking ties in favour of the first play in the order of
+//!   [`game::Game::valid_plays`].
+//!
+//! Evaluations expressed as `f64` (such as the win probabilities given by
+//! [`analysis::model::OutcomeModel::win_probability`], the [`analysis::evals::Evaluator`] trait
+//! and the evaluations reported by engines) are outside these guarantees, as functions like `exp`
+//! may give slightly different results on different platforms. Where results must match exactly,
+//! use the integer score instead.
 
 extern crate core;
 
-/// Miscellaneous utilities used elsewhere in the crate.
+/// Miscellaneous utilities, used elsewhere in the crate and also useful for building engines.
 #[macro_use]
-mod utils;
+pub mod utils;
 
 /// Code for defining game rules.
 pub mod rules;
@@ -92,4 +126,15 @@ pub mod play;
 pub mod preset;
 
 /// Code relating to the board, including board state and geometry.
-pub mod board;
\ No newline at end of file
+pub mod board;
+
+/// Tools for analysing game positions.
+pub mod analysis;
+
+/// Records of complete games, which can be stored, transferred and replayed.
+pub mod record;
+/// Interfaces for engines (ie, computer players), and tools for running games between them.
+pub mod engine;
+
+/// Puzzles (positions with a known solution) and tools for checking attempts to solve them.
+pub mod puzzle;
@@ -9,7 +9,7 @@ use crate::tiles::Tile;
 pub const KING: Piece = Piece { piece_type: King, side: Defender };
 
 /// The two sides of the game (attacker and defender).
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
 pub enum Side {
     Attacker = 0,
     Defender = 8
@@ -27,7 +27,7 @@ impl Side {
 }
 
 /// The different types of pieces that can occupy a board.
-#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, PartialOrd, Ord)]
 pub enum PieceType {
     King =      0b0000_0001,
     Soldier =   0b0000_0010,
@@ -59,7 +59,7 @@ impl BitOr<PieceType> for u16 {
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, PartialOrd, Ord)]
 /// A piece belonging to a particular side.
 pub struct Piece {
     pub piece_type: PieceType,
@@ -68,7 +68,7 @@ pub struct Piece {
 
 impl Piece {
     /// Create a new piece of the given type and side.
-    pub fn new(piece_type: PieceType, side: Side) -> Self {
+    pub const fn new(piece_type: PieceType, side: Side) -> Self {
         Self { piece_type, side }
     }
 
@@ -141,7 +141,7 @@ impl TryFrom<char> for Piece {
 }
 
 /// A struct representing a combination of a tile and a piece.
-#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash, PartialOrd, Ord)]
 pub struct PlacedPiece {
     pub tile: Tile,
     pub piece: Piece
@@ -153,7 +153,7 @@ impl PlacedPiece {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct PieceSet(u16);
 
 impl From<u16> for PieceSet {
@@ -317,4 +317,26 @@ mod tests {
             assert!(!ps.contains(Piece::new(Mercenary, s)));
         }
     }
+
+    #[test]
+    fn test_piece_ordering() {
+        let mut pieces = vec![
+            Piece::new(Soldier, Defender),
+            Piece::new(King, Defender),
+            Piece::new(Soldier, Attacker)
+        ];
+        pieces.sort();
+        assert_eq!(pieces, vec![
+            Piece::new(King, Defender),
+            Piece::new(Soldier, Attacker),
+            Piece::new(Soldier, Defender)
+        ]);
+        let sets: std::collections::HashSet<PieceSet> = [
+            PieceSet::from(King),
+            PieceSet::from(vec![King]),
+            PieceSet::default()
+        ].into();
+        assert_eq!(sets.len(), 2);
+        assert!(!PieceSet::default().contains(Piece::new(King, Defender)));
+    }
 }
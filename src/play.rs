@@ -4,13 +4,16 @@ use std::str::FromStr;
 use crate::board::state::BoardState;
 use crate::error::{BoardError, ParseError, PlayError};
 use crate::error::ParseError::{BadPlay, BadString};
-use crate::error::PlayError::DisjointTiles;
+use crate::error::PlayError::{BadEncoding, DisjointTiles, NotEncodable};
 use crate::game::logic::GameLogic;
 use crate::game::PlayEffects;
 use crate::game::state::GameState;
 use crate::pieces::{Piece, Side};
 use crate::tiles::Axis::{Horizontal, Vertical};
 
+/// The side length of the largest board whose plays can be encoded using [`Play::encode`].
+pub const MAX_ENCODABLE_BOARD_LEN: u8 = 19;
+
 /// A single move of a piece from one tile to another. (Named "Play" rather than "Move" as the lower-cased version of
 /// the latter would clash with the Rust keyword.)
 ///
@@ -19,7 +22,7 @@ use crate::tiles::Axis::{Horizontal, Vertical};
 /// lower-numbered row or column). This way, moves are guaranteed to be along a row or column (but
 /// are not guaranteed to be within the bounds of the board, nor are they guaranteed to be valid
 /// generally).
-#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash, PartialOrd, Ord)]
 pub struct Play {
     pub from: Tile,
     /// The axis along which the move occurs, ie, horizontal or vertical.
@@ -65,6 +68,45 @@ impl Play {
     pub fn to_coords(&self) -> Coords {
         Coords::from(self.from) + self.movement
     }
+
+    /// Encode the play as a `u16`, for compact storage or transmission. The encoding is the same
+    /// for all board sizes up to 19x19 ([`MAX_ENCODABLE_BOARD_LEN`]); plays which could not occur
+    /// on such a board cannot be encoded.
+    ///
+    /// The top 9 bits hold the index of the source tile (`row * 19 + col`), the next bit the axis
+    /// (set for horizontal) and the bottom 6 bits the displacement plus 18.
+    pub fn encode(&self) -> Result<u16, PlayError> {
+        let max = MAX_ENCODABLE_BOARD_LEN;
+        let to = self.to_coords();
+        if self.from.row >= max || self.from.col >= max || self.movement.displacement == 0
+            || !(0..max as i8).contains(&to.row) || !(0..max as i8).contains(&to.col) {
+            return Err(NotEncodable)
+        }
+        let index = self.from.row as u16 * max as u16 + self.from.col as u16;
+        let axis = if self.movement.axis == Horizontal { 1 } else { 0 };
+        let displacement = (self.movement.displacement + (max as i8 - 1)) as u16;
+        Ok(index << 7 | axis << 6 | displacement)
+    }
+
+    /// Decode a play from the encoding produced by [`Self::encode`].
+    pub fn decode(code: u16) -> Result<Self, PlayError> {
+        let max = MAX_ENCODABLE_BOARD_LEN as u16;
+        let index = code >> 7;
+        let axis = if code & (1 << 6) != 0 { Horizontal } else { Vertical };
+        let displacement = (code & 0x3f) as i8 - (max as i8 - 1);
+        if index >= max * max {
+            return Err(BadEncoding(code))
+        }
+        let play = Self::new(
+            Tile::new((index / max) as u8, (index % max) as u8),
+            AxisOffset::new(axis, displacement)
+        );
+        // Re-encoding rejects zero displacements and destinations off the board.
+        match play.encode() {
+            Ok(c) if c == code => Ok(play),
+            _ => Err(BadEncoding(code))
+        }
+    }
 }
 
 impl FromStr for Play {
@@ -98,7 +140,7 @@ impl Display for Play {
 /// Passing a `ValidPlay` around an invalid `Play` to a function can cause panics or bad program
 /// state. It is generally preferable to create a `ValidPlay` by passing a `Play` to the
 /// [`GameLogic::validate_play`] method.
-#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash, PartialOrd, Ord)]
 pub struct ValidPlay { pub play: Play }
 
 /// A record of a single play.
@@ -223,4 +265,31 @@ impl<'logic, 'state, T: BoardState> Iterator for ValidPlayIterator<'logic, 'stat
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::PlayError::{BadEncoding, NotEncodable};
+    use crate::play::Play;
+    use crate::tiles::Tile;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_play_encoding() {
+        for s in ["a1-a2", "d4-d1", "a1-s1", "s19-s1", "k11-b11", "c5-c19"] {
+            let play = Play::from_str(s).unwrap();
+            let code = play.encode().unwrap();
+            assert_eq!(Play::decode(code), Ok(play));
+        }
+        assert_eq!(Play::from_str("a1-a2").unwrap().encode(), Ok(18 + 1));
+        assert_eq!(Play::from_str("b1-a1").unwrap().encode(), Ok((1 << 7) | (1 << 6) | 17));
+        assert_eq!(Play::from_str("a1-a20").unwrap().encode(), Err(NotEncodable));
+        assert_eq!(Play::from_tiles(Tile::new(0, 0), Tile::new(0, 0)).unwrap().encode(), Err(NotEncodable));
+
+        // Zero displacement
+        assert_eq!(Play::decode(18), Err(BadEncoding(18)));
+        // Destination off the board
+        assert_eq!(Play::decode(40), Err(BadEncoding(40)));
+        assert_eq!(Play::decode(u16::MAX), Err(BadEncoding(u16::MAX)));
+    }
+}
@@ -3,14 +3,16 @@ pub mod rules {
     use crate::pieces::PieceType::{King, Soldier};
     use crate::pieces::Side::Attacker;
     use crate::rules::KingAttack::Armed;
-    use crate::rules::{HostilityRules, RepetitionRule, Ruleset, ShieldwallRules};
+    use crate::rules::{GuardCapture, HostilityRules, PieceValues, RepetitionRule, Ruleset, ShieldwallRules, SpecialTileRules};
     use crate::rules::EnclosureWinRules::WithoutEdgeAccess;
     use crate::rules::KingStrength::{Strong, StrongByThrone};
-    use crate::rules::ThroneRule::{KingEntry, NoEntry};
+    use crate::rules::ThroneRule::{KingEntry, NoEntry, NoPass};
+    use crate::tiles::Tile;
 
     /// Rules for Copenhagen Hnefatafl.
     pub const COPENHAGEN: Ruleset = Ruleset {
         edge_escape: false,
+        king_target: None,
         king_strength: Strong,
         king_attack: Armed,
         shieldwall: Some(ShieldwallRules {
@@ -25,17 +27,31 @@ pub mod rules {
             corners: PieceSet::from_piece_type(Soldier),
             edge: PieceSet::none()
         },
+        special_tiles: &[],
         slow_pieces: PieceSet::none(),
+        range_limits: &[],
+        promotions: &[],
+        swap_rule: false,
+        double_play: None,
+        first_play: None,
         starting_side: Attacker,
         enclosure_win: Some(WithoutEdgeAccess),
+        throne_occupation_win: false,
+        annihilation_win: false,
+        capture_win: None,
         repetition_rule: Some(RepetitionRule { n_repetitions: 3, is_loss: true }),
+        move_limit: None,
         draw_on_no_plays: false,
         linnaean_capture: false,
+        commander_lone_capture: false,
+        guard_capture: GuardCapture::Normal,
+        piece_values: PieceValues::standard(),
     };
 
     /// Rules for Federation Brandubh.
     pub const BRANDUBH: Ruleset = Ruleset {
         edge_escape: false,
+        king_target: None,
         king_strength: StrongByThrone,
         king_attack: Armed,
         shieldwall: None,
@@ -47,17 +63,31 @@ pub mod rules {
             corners: PieceSet::all(),
             edge: PieceSet::none()
         },
+        special_tiles: &[],
         slow_pieces: PieceSet::none(),
+        range_limits: &[],
+        promotions: &[],
+        swap_rule: false,
+        double_play: None,
+        first_play: None,
         starting_side: Attacker,
         enclosure_win: Some(WithoutEdgeAccess),
+        throne_occupation_win: false,
+        annihilation_win: false,
+        capture_win: None,
         repetition_rule: Some(RepetitionRule { n_repetitions: 3, is_loss: true }),
+        move_limit: None,
         draw_on_no_plays: false,
-        linnaean_capture: false
+        linnaean_capture: false,
+        commander_lone_capture: false,
+        guard_capture: GuardCapture::Normal,
+        piece_values: PieceValues::standard()
     };
 
     /// Rules for Magpie.
     pub const MAGPIE: Ruleset = Ruleset {
         edge_escape: false,
+        king_target: None,
         king_strength: Strong,
         king_attack: Armed,
         shieldwall: None,
@@ -69,17 +99,31 @@ pub mod rules {
             corners: PieceSet::all(),
             edge: PieceSet::none(),
         },
+        special_tiles: &[],
         slow_pieces: PieceSet::from_piece_type(King),
+        range_limits: &[],
+        promotions: &[],
+        swap_rule: false,
+        double_play: None,
+        first_play: None,
         starting_side: Attacker,
         enclosure_win: None,
+        throne_occupation_win: false,
+        annihilation_win: false,
+        capture_win: None,
         repetition_rule: None,
+        move_limit: None,
         draw_on_no_plays: false,
-        linnaean_capture: false
+        linnaean_capture: false,
+        commander_lone_capture: false,
+        guard_capture: GuardCapture::Normal,
+        piece_values: PieceValues::standard()
     };
 
     /// Rules for Linnaeus Tablut.
     pub const TABLUT: Ruleset = Ruleset {
         edge_escape: true,
+        king_target: None,
         king_strength: StrongByThrone,
         king_attack: Armed,
         shieldwall: None,
@@ -91,12 +135,82 @@ pub mod rules {
             corners: PieceSet::none(),
             edge: PieceSet::none()
         },
+        special_tiles: &[],
         slow_pieces: PieceSet::none(),
+        range_limits: &[],
+        promotions: &[],
+        swap_rule: false,
+        double_play: None,
+        first_play: None,
         starting_side: Attacker,
         enclosure_win: None,
+        throne_occupation_win: false,
+        annihilation_win: false,
+        capture_win: None,
         repetition_rule: Some(RepetitionRule { n_repetitions: 3, is_loss: false }),
+        move_limit: None,
         draw_on_no_plays: true,
-        linnaean_capture: true
+        linnaean_capture: true,
+        commander_lone_capture: false,
+        guard_capture: GuardCapture::Normal,
+        piece_values: PieceValues::standard()
+    };
+
+    /// Create a camp on a 9x9 Tablut board, which is hostile to all pieces and which no piece may
+    /// enter or pass through, except for attackers that have not yet left it.
+    const fn tablut_camp(tiles: &'static [Tile]) -> SpecialTileRules {
+        SpecialTileRules {
+            name: "camp",
+            tiles,
+            hostile_to: PieceSet::all(),
+            may_enter: PieceSet::none(),
+            may_pass: PieceSet::none(),
+            residents_exempt: true
+        }
+    }
+
+    /// The four attacker camps on a 9x9 Tablut board.
+    const TABLUT_CAMPS: [SpecialTileRules; 4] = [
+        tablut_camp(&[Tile::new(0, 3), Tile::new(0, 4), Tile::new(0, 5), Tile::new(1, 4)]),
+        tablut_camp(&[Tile::new(3, 8), Tile::new(4, 8), Tile::new(5, 8), Tile::new(4, 7)]),
+        tablut_camp(&[Tile::new(8, 3), Tile::new(8, 4), Tile::new(8, 5), Tile::new(7, 4)]),
+        tablut_camp(&[Tile::new(3, 0), Tile::new(4, 0), Tile::new(5, 0), Tile::new(4, 1)])
+    ];
+
+    /// Rules for Tablut as played with attacker camps (sometimes called "Ashton" Tablut).
+    pub const ASHTON_TABLUT: Ruleset = Ruleset {
+        edge_escape: true,
+        king_target: None,
+        king_strength: StrongByThrone,
+        king_attack: Armed,
+        shieldwall: None,
+        exit_fort: false,
+        throne_movement: NoPass,
+        may_enter_corners: PieceSet::all(),
+        hostility: HostilityRules {
+            throne: PieceSet::all(),
+            corners: PieceSet::none(),
+            edge: PieceSet::none()
+        },
+        special_tiles: &TABLUT_CAMPS,
+        slow_pieces: PieceSet::none(),
+        range_limits: &[],
+        promotions: &[],
+        swap_rule: false,
+        double_play: None,
+        first_play: None,
+        starting_side: Attacker,
+        enclosure_win: None,
+        throne_occupation_win: false,
+        annihilation_win: false,
+        capture_win: None,
+        repetition_rule: Some(RepetitionRule { n_repetitions: 3, is_loss: false }),
+        move_limit: None,
+        draw_on_no_plays: false,
+        linnaean_capture: false,
+        commander_lone_capture: false,
+        guard_capture: GuardCapture::Normal,
+        piece_values: PieceValues::standard()
     };
 }
 
@@ -109,4 +223,6 @@ pub mod boards {
     pub const MAGPIE: &str = "3t3/1t3t1/3T3/t1TKT1t/3T3/1t3t1/3t3";
     
     pub const TABLUT: &str = "3ttt3/4t4/4T4/t3T3t/ttTTKTTtt/t3T3t/4T4/4t4/3ttt3";
+
+    pub const ASHTON_TABLUT: &str = TABLUT;
 }
\ No newline at end of file
@@ -0,0 +1,336 @@
+use crate::board::state::BoardState;
+use crate::error::ParseError::{BadString, UnsupportedVersion};
+use crate::error::{ParseError, PlayInvalid, PuzzleError};
+use crate::game::GameOutcome::Win;
+use crate::game::GameStatus::{Ongoing, Over};
+use crate::game::{Game, WinReason};
+use crate::pieces::Side;
+use crate::pieces::Side::{Attacker, Defender};
+use crate::play::Play;
+use crate::record::{parse_tag, record_version};
+use crate::rules::Ruleset;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The current version of the text format of a [`Puzzle`], which is written in its `Version` tag.
+pub const PUZZLE_FORMAT_VERSION: u32 = 1;
+
+/// Functions upgrading a puzzle in text form from one version of the format to the next. The
+/// function at index `i` converts from version `i + 1` to version `i + 2`.
+const MIGRATIONS: [fn(&str) -> String; PUZZLE_FORMAT_VERSION as usize - 1] = [];
+
+/// What the solver of a [`Puzzle`] must achieve.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Objective {
+    /// Win the game, by any means.
+    Win,
+    /// Get the king to safety (by escaping, reaching an exit fort or reaching the target tile).
+    KingEscape,
+    /// Capture the king.
+    KingCapture
+}
+
+impl Objective {
+
+    /// Whether the given game has ended in a way that achieves this objective for the given side.
+    pub fn is_met<T: BoardState>(&self, game: &Game<T>, side: Side) -> bool {
+        let Over(Win(reason, winner)) = game.state.status else {
+            return false
+        };
+        winner == side && match self {
+            Objective::Win => true,
+            Objective::KingEscape => matches!(
+                reason,
+                WinReason::KingEscaped | WinReason::ExitFort | WinReason::KingReachedTarget
+            ),
+            Objective::KingCapture => reason == WinReason::KingCaptured
+        }
+    }
+}
+
+impl Display for Objective {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Objective::Win => "win",
+            Objective::KingEscape => "escape",
+            Objective::KingCapture => "capture"
+        })
+    }
+}
+
+impl FromStr for Objective {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "win" => Ok(Objective::Win),
+            "escape" => Ok(Objective::KingEscape),
+            "capture" => Ok(Objective::KingCapture),
+            _ => Err(BadString(s.to_string()))
+        }
+    }
+}
+
+/// The result of checking an attempt to solve a [`Puzzle`] using [`Puzzle::verify`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum AttemptResult {
+    /// The attempt achieves the puzzle's objective.
+    Solved,
+    /// The attempt follows a solution line so far, but does not yet achieve the objective.
+    Incomplete,
+    /// The solver's play at the given index departs from every solution line without achieving
+    /// the objective.
+    Incorrect { index: usize },
+    /// The opponent's reply at the given index is not covered by any solution line, so the
+    /// solver's later plays cannot be judged (unless they achieve the objective).
+    OffBook { index: usize },
+    /// The play at the given index is not valid under the rules.
+    Invalid { index: usize, reason: PlayInvalid }
+}
+
+/// A puzzle: a position, the side to play and an objective for that side, together with one or
+/// more solution lines.
+///
+/// Each solution line is a sequence of plays starting with a play by the solver and alternating
+/// with the opponent's replies, ending in a position where the objective has been achieved. The
+/// lines together should cover the opponent's reasonable defences.
+///
+/// Like a [`GameRecord`](crate::record::GameRecord), a puzzle does not include the rules, which
+/// must be supplied when it is used. As text, a puzzle consists of tags of the form
+/// `[Name "value"]`, one per line, followed by one line for each solution line. For example:
+///
+/// ```text
+/// [Version "1"]
+/// [Board "7/1K5/7/4t2/7/5t1/7"]
+/// [ToPlay "defender"]
+/// [Objective "escape"]
+/// b2-b1 e4-e1 b1-a1
+/// b2-b1 f6-f1 b1-a1
+/// ```
+///
+/// Puzzles are always written in the current version of the format ([`PUZZLE_FORMAT_VERSION`]).
+/// Puzzles written in older versions are migrated when parsed; a puzzle without a `Version` tag
+/// is assumed to be version 1.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Puzzle {
+    /// The position, in FEN format.
+    pub board: String,
+    /// The side to play, which is the side solving the puzzle.
+    pub side_to_play: Side,
+    /// What the side to play must achieve.
+    pub objective: Objective,
+    /// The solution lines.
+    pub solutions: Vec<Vec<Play>>
+}
+
+impl Puzzle {
+
+    /// Create a new game at the puzzle's position, with the solver to play.
+    pub fn game<T: BoardState>(&self, rules: Ruleset) -> Result<Game<T>, ParseError> {
+        Game::new(Ruleset { starting_side: self.side_to_play, ..rules }, &self.board)
+    }
+
+    /// Check that the puzzle is well formed under the given rules: that it has at least one
+    /// solution line, and that each line consists of valid plays and achieves the objective.
+    pub fn validate<T: BoardState>(&self, rules: Ruleset) -> Result<(), PuzzleError> {
+        if self.solutions.is_empty() {
+            return Err(PuzzleError::NoSolution)
+        }
+        for (line, plays) in self.solutions.iter().enumerate() {
+            let mut game = self.game::<T>(rules).map_err(PuzzleError::BadPosition)?;
+            for (index, play) in plays.iter().enumerate() {
+                game.do_play(*play)
+                    .map_err(|reason| PuzzleError::InvalidPlay { line, index, reason })?;
+            }
+            if !self.objective.is_met(&game, self.side_to_play) {
+                return Err(PuzzleError::ObjectiveNotMet { line })
+            }
+        }
+        Ok(())
+    }
+
+    /// Check an attempted line of play (including the opponent's replies) against the puzzle,
+    /// using the given rules. An attempt which achieves the objective is accepted even if it is
+    /// not one of the solution lines. Only the solver's plays are judged: an opponent's reply
+    /// which is not covered by the solution lines takes the attempt off book.
+    ///
+    /// Returns an error if the puzzle's position cannot be parsed.
+    pub fn verify<T: BoardState>(
+        &self,
+        rules: Ruleset,
+        attempt: &[Play]
+    ) -> Result<AttemptResult, ParseError> {
+        let mut game = self.game::<T>(rules)?;
+        for (index, play) in attempt.iter().enumerate() {
+            if let Err(reason) = game.do_play(*play) {
+                return Ok(AttemptResult::Invalid { index, reason })
+            }
+        }
+        if self.objective.is_met(&game, self.side_to_play) {
+            return Ok(AttemptResult::Solved)
+        }
+        let matched = self.solutions.iter()
+            .map(|line| line.iter().zip(attempt).take_while(|(a, b)| a == b).count())
+            .max()
+            .unwrap_or(0);
+        if matched < attempt.len() {
+            // The solver's plays are those with even indices.
+            return Ok(if matched % 2 == 0 {
+                AttemptResult::Incorrect { index: matched }
+            } else {
+                AttemptResult::OffBook { index: matched }
+            })
+        }
+        if game.state.status != Ongoing {
+            // The attempt follows a solution line, but the game ended without achieving the
+            // objective, so the puzzle itself is faulty. Blame the solver's last play.
+            let last = attempt.len().saturating_sub(1);
+            return Ok(AttemptResult::Incorrect { index: last - last % 2 })
+        }
+        Ok(AttemptResult::Incomplete)
+    }
+
+    /// The next play in the first solution line which begins with the given plays, if any. This
+    /// can be used to choose the opponent's reply to the solver's play, or to give the solver a
+    /// hint.
+    pub fn next_play(&self, attempt: &[Play]) -> Option<Play> {
+        self.solutions.iter()
+            .find(|line| line.len() > attempt.len() && line.starts_with(attempt))
+            .map(|line| line[attempt.len()])
+    }
+}
+
+impl Display for Puzzle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "[Version \"{PUZZLE_FORMAT_VERSION}\"]")?;
+        writeln!(f, "[Board \"{}\"]", self.board)?;
+        let side = match self.side_to_play {
+            Attacker => "attacker",
+            Defender => "defender"
+        };
+        writeln!(f, "[ToPlay \"{side}\"]")?;
+        write!(f, "[Objective \"{}\"]", self.objective)?;
+        for line in &self.solutions {
+            let plays: Vec<String> = line.iter().map(Play::to_string).collect();
+            write!(f, "\n{}", plays.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Puzzle {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let version = record_version(s)?;
+        if version > PUZZLE_FORMAT_VERSION {
+            return Err(UnsupportedVersion(version))
+        }
+        let mut migrated = s.to_string();
+        for migrate in &MIGRATIONS[(version as usize - 1)..] {
+            migrated = migrate(&migrated);
+        }
+        parse_current_puzzle(&migrated)
+    }
+}
+
+/// Parse a puzzle written in the current version of the format.
+fn parse_current_puzzle(s: &str) -> Result<Puzzle, ParseError> {
+    let mut board = None;
+    let mut side_to_play = None;
+    let mut objective = None;
+    let mut solutions = vec![];
+    for line in s.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if line.starts_with('[') {
+            let (name, value) = parse_tag(line)?;
+            match name {
+                "Board" => board = Some(value.to_string()),
+                "ToPlay" => side_to_play = Some(match value {
+                    "attacker" => Attacker,
+                    "defender" => Defender,
+                    _ => return Err(BadString(value.to_string()))
+                }),
+                "Objective" => objective = Some(value.parse()?),
+                // Ignore unknown tags, for forward compatibility.
+                _ => {}
+            }
+        } else {
+            solutions.push(line.split_whitespace()
+                .map(Play::from_str)
+                .collect::<Result<Vec<_>, _>>()?);
+        }
+    }
+    Ok(Puzzle {
+        board: board.ok_or(BadString(String::from("Missing Board tag.")))?,
+        side_to_play: side_to_play.ok_or(BadString(String::from("Missing ToPlay tag.")))?,
+        objective: objective.ok_or(BadString(String::from("Missing Objective tag.")))?,
+        solutions
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::state::SmallBasicBoardState;
+    use crate::error::PlayInvalid::WrongPlayer;
+    use crate::error::ParseError::UnsupportedVersion;
+    use crate::error::PuzzleError;
+    use crate::pieces::Side::Defender;
+    use crate::play::Play;
+    use crate::preset::rules;
+    use crate::puzzle::{AttemptResult, Objective, Puzzle};
+    use std::str::FromStr;
+
+    fn plays(s: &str) -> Vec<Play> {
+        s.split_whitespace().map(|p| Play::from_str(p).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_puzzle() {
+        let text = [
+            "[Version \"1\"]",
+            "[Board \"7/1K5/7/4t2/7/5t1/7\"]",
+            "[ToPlay \"defender\"]",
+            "[Objective \"escape\"]",
+            "b2-b1 e4-e1 b1-a1",
+            "b2-b1 f6-f1 b1-a1"
+        ].join("\n");
+        let puzzle = Puzzle::from_str(&text).unwrap();
+        assert_eq!(puzzle.side_to_play, Defender);
+        assert_eq!(puzzle.objective, Objective::KingEscape);
+        assert_eq!(puzzle.solutions.len(), 2);
+        assert_eq!(puzzle.to_string(), text);
+        // Puzzles without a version tag are version 1.
+        assert_eq!(Puzzle::from_str(text.strip_prefix("[Version \"1\"]\n").unwrap()), Ok(puzzle.clone()));
+        assert_eq!(
+            Puzzle::from_str(&text.replace("[Version \"1\"]", "[Version \"99\"]")),
+            Err(UnsupportedVersion(99))
+        );
+        assert_eq!(puzzle.validate::<SmallBasicBoardState>(rules::BRANDUBH), Ok(()));
+
+        let verify = |s: &str| puzzle.verify::<SmallBasicBoardState>(rules::BRANDUBH, &plays(s)).unwrap();
+        assert_eq!(verify(""), AttemptResult::Incomplete);
+        assert_eq!(verify("b2-b1"), AttemptResult::Incomplete);
+        assert_eq!(verify("b2-b1 e4-e1 b1-a1"), AttemptResult::Solved);
+        // The opponent's reply is not covered by the solution, but the king still escapes.
+        assert_eq!(verify("b2-b1 e4-e2 b1-g1"), AttemptResult::Solved);
+        assert_eq!(verify("b2-b3"), AttemptResult::Incorrect { index: 0 });
+        assert_eq!(verify("b2-b1 e4-e1 b1-b2"), AttemptResult::Incorrect { index: 2 });
+        // The opponent's reply is not covered by the solution, which is not the solver's fault.
+        assert_eq!(verify("b2-b1 e4-e2"), AttemptResult::OffBook { index: 1 });
+        assert_eq!(verify("b2-b1 e4-e2 b1-b2"), AttemptResult::OffBook { index: 1 });
+        assert_eq!(verify("b2-b1 b1-a1"), AttemptResult::Invalid { index: 1, reason: WrongPlayer });
+
+        assert_eq!(puzzle.next_play(&plays("b2-b1")), Some(plays("e4-e1")[0]));
+        assert_eq!(puzzle.next_play(&plays("b2-b1 f6-f1")), Some(plays("b1-a1")[0]));
+        assert_eq!(puzzle.next_play(&plays("b2-b1 e4-e1 b1-a1")), None);
+
+        let mut bad = puzzle.clone();
+        bad.solutions.push(plays("b2-b1 e4-e1"));
+        assert_eq!(
+            bad.validate::<SmallBasicBoardState>(rules::BRANDUBH),
+            Err(PuzzleError::ObjectiveNotMet { line: 2 })
+        );
+        bad.solutions.clear();
+        assert_eq!(bad.validate::<SmallBasicBoardState>(rules::BRANDUBH), Err(PuzzleError::NoSolution));
+    }
+}
@@ -0,0 +1,600 @@
+use crate::board::state::BoardState;
+use crate::error::ParseError::{BadString, UnsupportedVersion};
+use crate::error::RecordError::{BadPosition, ChecksumMismatch, InvalidPlay, MissingChecksum};
+use crate::error::{ArchiveError, ParseError, RecordError};
+use crate::game::clock::TimeControl;
+use crate::game::Game;
+use crate::game::GameOutcome::{Draw, Win};
+use crate::game::GameStatus::{Ongoing, Over};
+use crate::play::Play;
+use crate::rules::Ruleset;
+use crate::utils::FnvHasher;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::hash::Hasher;
+use std::io::{BufRead, Lines};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// The current version of the text format of a [`GameRecord`], which is written in its `Version`
+/// tag.
+pub const RECORD_FORMAT_VERSION: u32 = 2;
+
+/// Functions upgrading a record in text form from one version of the format to the next. The
+/// function at index `i` converts from version `i + 1` to version `i + 2`.
+const MIGRATIONS: [fn(&str) -> String; RECORD_FORMAT_VERSION as usize - 1] = [
+    migrate_v1_to_v2
+];
+
+/// Version 1 (which had no `Version` tag) differs from version 2 only in lacking play
+/// annotations, so every version 1 record is also a valid version 2 record.
+fn migrate_v1_to_v2(s: &str) -> String {
+    s.to_string()
+}
+
+/// A record of a game: its starting position and the plays made, which is sufficient (together
+/// with the rules) to replay the game.
+///
+/// A record can optionally carry a checksum over the rules, starting position and plays, so that
+/// truncation or tampering can be detected (using [`Self::verify`]) before attempting to replay
+/// it.
+///
+/// As text, a record consists of a number of tags of the form `[Name "value"]`, one per line,
+/// followed by a line listing the plays separated by spaces. Any play may be followed by a
+/// [`PlayAnnotation`] enclosed in braces. For example:
+///
+/// ```text
+/// [Version "2"]
+/// [Board "3t3/3t3/3T3/ttTKTtt/3T3/3t3/3t3"]
+/// [Checksum "0123456789abcdef"]
+/// d1-c1 {eval 0.25 clock 59.5} d3-c3 c1-c2 {eval -1}
+/// ```
+///
+/// Records are always written in the current version of the format
+/// ([`RECORD_FORMAT_VERSION`]). Records written in older versions are migrated when parsed; a
+/// record without a `Version` tag is assumed to be version 1.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GameRecord {
+    /// The starting position of the game, in FEN format.
+    pub starting_board: String,
+    /// Whether the players swapped sides under the pie rule after the first play.
+    pub sides_swapped: bool,
+    /// The time control under which the game was played, if it was timed.
+    pub time_control: Option<TimeControl>,
+    /// The plays made in the game.
+    pub plays: Vec<Play>,
+    /// Annotations on the plays made in the game, keyed by the index of the play in
+    /// [`Self::plays`]. Annotations are not covered by the checksum.
+    pub annotations: BTreeMap<usize, PlayAnnotation>,
+    /// A checksum over the record's contents and the rules of the game, if the record has been
+    /// sealed.
+    pub checksum: Option<u64>
+}
+
+impl GameRecord {
+
+    /// Create a record of the given game (unsealed).
+    pub fn from_game<T: BoardState>(game: &Game<T>) -> Self {
+        let starting_board = game.state_at(0)
+            .expect("Starting state should always be available.")
+            .board
+            .to_fen();
+        Self {
+            starting_board,
+            sides_swapped: game.state.sides_swapped,
+            time_control: None,
+            plays: game.play_history.iter().map(|r| r.play).collect(),
+            annotations: BTreeMap::new(),
+            checksum: None
+        }
+    }
+
+    /// Calculate the checksum of the record when played under the given rules. The checksum
+    /// covers the rules, the starting position, whether sides were swapped and every play, and is
+    /// the same on every platform.
+    ///
+    /// **NOTE**: The contribution of the rules is based on their debug representation, so records
+    /// sealed with one version of this crate may not verify with a version which adds new rules.
+    pub fn compute_checksum(&self, rules: &Ruleset) -> u64 {
+        let mut hasher = FnvHasher::default();
+        hasher.write(format!("{rules:?}").as_bytes());
+        hasher.write(&[0]);
+        hasher.write(self.starting_board.as_bytes());
+        hasher.write(&[0, self.sides_swapped as u8]);
+        hasher.write(&(self.plays.len() as u64).to_le_bytes());
+        for play in &self.plays {
+            hasher.write(play.to_string().as_bytes());
+            hasher.write(&[0]);
+        }
+        hasher.finish()
+    }
+
+    /// Calculate and store the checksum of the record when played under the given rules.
+    pub fn seal(&mut self, rules: &Ruleset) {
+        self.checksum = Some(self.compute_checksum(rules));
+    }
+
+    /// Check that the stored checksum matches the contents of the record when played under the
+    /// given rules.
+    pub fn verify(&self, rules: &Ruleset) -> Result<(), RecordError> {
+        let expected = self.checksum.ok_or(MissingChecksum)?;
+        let actual = self.compute_checksum(rules);
+        if expected != actual {
+            return Err(ChecksumMismatch { expected, actual })
+        }
+        Ok(())
+    }
+
+    /// Replay the game under the given rules. This does not check the checksum; call
+    /// [`Self::verify`] first if required.
+    pub fn replay<T: BoardState>(&self, rules: Ruleset) -> Result<Game<T>, RecordError> {
+        self.replay_with(rules, |_| {})
+    }
+
+    /// Replay the game under the given rules, calling `on_play` with the game after each play.
+    pub(crate) fn replay_with<T: BoardState>(
+        &self,
+        rules: Ruleset,
+        mut on_play: impl FnMut(&Game<T>)
+    ) -> Result<Game<T>, RecordError> {
+        let mut game: Game<T> = Game::new(rules, &self.starting_board).map_err(BadPosition)?;
+        for (index, play) in self.plays.iter().enumerate() {
+            if index == 1 && self.sides_swapped {
+                game.swap_sides().map_err(|reason| InvalidPlay { index, reason })?;
+            }
+            game.do_play(*play).map_err(|reason| InvalidPlay { index, reason })?;
+            on_play(&game);
+        }
+        if self.sides_swapped && self.plays.len() <= 1 {
+            let index = self.plays.len();
+            game.swap_sides().map_err(|reason| InvalidPlay { index, reason })?;
+        }
+        Ok(game)
+    }
+
+    /// Produce a human-readable write-up of the game under the given rules: a numbered list of
+    /// plays (with captures), interleaved with board diagrams as specified by `options`, followed
+    /// by the result.
+    pub fn print<T: BoardState>(
+        &self,
+        rules: Ruleset,
+        options: &PrintOptions
+    ) -> Result<String, RecordError> {
+        let mut lines = vec![format!("Board: {}", self.starting_board)];
+        let n_plays = self.plays.len();
+        let game: Game<T> = self.replay_with(rules, |game: &Game<T>| {
+            let n = game.play_history.len();
+            let record = game.play_history.last().expect("A play should have been made.");
+            lines.push(format!("{n}. {:?} {record}", record.side));
+            if n == 1 && self.sides_swapped {
+                lines.push(String::from("Sides swapped."));
+            }
+            let diagram = options.every.is_some_and(|every| every > 0 && n.is_multiple_of(every))
+                || (options.on_capture && !record.effects.captures.is_empty())
+                || (options.final_position && n == n_plays);
+            if diagram {
+                for row in game.state.board.to_display_str().lines() {
+                    lines.push(format!("    {row}"));
+                }
+            }
+        })?;
+        lines.push(match game.state.status {
+            Over(Win(reason, side)) => format!("Result: {side:?} won ({reason:?})"),
+            Over(Draw(reason)) => format!("Result: Draw ({reason:?})"),
+            Ongoing => String::from("Result: Ongoing")
+        });
+        Ok(lines.join("\n"))
+    }
+}
+
+/// Structured data attached to a single play in a [`GameRecord`].
+///
+/// As text, an annotation is written as space-separated key-value pairs enclosed in braces, such
+/// as `{eval 0.25 clock 59.5}`. Unknown keys are ignored when parsing.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct PlayAnnotation {
+    /// The evaluation of the position after the play, from the attacker's perspective (positive
+    /// values favour the attacker).
+    pub eval: Option<f64>,
+    /// The time remaining on the clock of the side that made the play, after making it. Stored in
+    /// text to millisecond precision.
+    pub clock: Option<Duration>
+}
+
+impl PlayAnnotation {
+
+    /// Whether the annotation carries no data.
+    pub fn is_empty(&self) -> bool {
+        self.eval.is_none() && self.clock.is_none()
+    }
+}
+
+impl Display for PlayAnnotation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut fields = vec![];
+        if let Some(eval) = self.eval {
+            fields.push(format!("eval {eval}"));
+        }
+        if let Some(clock) = self.clock {
+            let millis = clock.as_millis();
+            fields.push(format!("clock {}.{:03}", millis / 1000, millis % 1000));
+        }
+        write!(f, "{{{}}}", fields.join(" "))
+    }
+}
+
+impl FromStr for PlayAnnotation {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bad_string = || BadString(s.to_string());
+        let inner = s.trim()
+            .strip_prefix('{')
+            .and_then(|s| s.strip_suffix('}'))
+            .ok_or_else(bad_string)?;
+        let mut annotation = Self::default();
+        let mut tokens = inner.split_whitespace();
+        while let Some(key) = tokens.next() {
+            let value = tokens.next().ok_or_else(bad_string)?;
+            match key {
+                "eval" => annotation.eval = Some(value.parse().map_err(|_| bad_string())?),
+                "clock" => {
+                    let secs: f64 = value.parse().map_err(|_| bad_string())?;
+                    annotation.clock = Some(
+                        Duration::try_from_secs_f64(secs).map_err(|_| bad_string())?
+                    );
+                },
+                // Ignore unknown keys, for forward compatibility.
+                _ => {}
+            }
+        }
+        Ok(annotation)
+    }
+}
+
+/// Options specifying when board diagrams are included by [`GameRecord::print`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PrintOptions {
+    /// Include a diagram after every `every` plays.
+    pub every: Option<usize>,
+    /// Include a diagram after each play that captures a piece.
+    pub on_capture: bool,
+    /// Include a diagram of the final position.
+    pub final_position: bool
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        Self { every: None, on_capture: true, final_position: true }
+    }
+}
+
+impl Display for GameRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "[Version \"{RECORD_FORMAT_VERSION}\"]")?;
+        writeln!(f, "[Board \"{}\"]", self.starting_board)?;
+        if self.sides_swapped {
+            writeln!(f, "[Swapped \"true\"]")?;
+        }
+        if let Some(time_control) = self.time_control {
+            writeln!(f, "[TimeControl \"{time_control}\"]")?;
+        }
+        if let Some(checksum) = self.checksum {
+            writeln!(f, "[Checksum \"{checksum:016x}\"]")?;
+        }
+        let plays: Vec<String> = self.plays.iter().enumerate().map(|(i, p)| {
+            match self.annotations.get(&i) {
+                Some(a) if !a.is_empty() => format!("{p} {a}"),
+                _ => p.to_string()
+            }
+        }).collect();
+        write!(f, "{}", plays.join(" "))
+    }
+}
+
+/// Parse a tag of the form `[Name "value"]`, returning the name and value.
+pub(crate) fn parse_tag(line: &str) -> Result<(&str, &str), ParseError> {
+    line.strip_prefix('[')
+        .and_then(|l| l.strip_suffix(']'))
+        .and_then(|l| l.split_once(' '))
+        .and_then(|(name, value)| Some((name, value.strip_prefix('"')?.strip_suffix('"')?)))
+        .ok_or(BadString(line.to_string()))
+}
+
+impl FromStr for GameRecord {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let version = record_version(s)?;
+        if version > RECORD_FORMAT_VERSION {
+            return Err(UnsupportedVersion(version))
+        }
+        let mut migrated = s.to_string();
+        for migrate in &MIGRATIONS[(version as usize - 1)..] {
+            migrated = migrate(&migrated);
+        }
+        parse_current_record(&migrated)
+    }
+}
+
+/// Get the format version of a record in text form, from its `Version` tag (or 1 if it has
+/// none).
+pub fn record_version(s: &str) -> Result<u32, ParseError> {
+    for line in s.lines().map(str::trim).filter(|l| l.starts_with('[')) {
+        if let ("Version", value) = parse_tag(line)? {
+            return match value.parse()? {
+                0 => Err(BadString(value.to_string())),
+                v => Ok(v)
+            }
+        }
+    }
+    Ok(1)
+}
+
+/// Parse a record written in the current version of the format.
+fn parse_current_record(s: &str) -> Result<GameRecord, ParseError> {
+    let mut record = GameRecord::default();
+    let mut board = None;
+    for line in s.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if line.starts_with('[') {
+            let (name, value) = parse_tag(line)?;
+            match name {
+                "Board" => board = Some(value.to_string()),
+                "Swapped" => record.sides_swapped = value == "true",
+                "TimeControl" => record.time_control = Some(value.parse()?),
+                "Checksum" => record.checksum = Some(
+                    u64::from_str_radix(value, 16).map_err(|_| BadString(value.to_string()))?
+                ),
+                // Ignore unknown tags, for forward compatibility.
+                _ => {}
+            }
+        } else {
+            let mut rest = line;
+            while !rest.is_empty() {
+                if rest.starts_with('{') {
+                    let end = rest.find('}').ok_or(BadString(rest.to_string()))?;
+                    let index = record.plays.len().checked_sub(1)
+                        .ok_or(BadString(rest.to_string()))?;
+                    record.annotations.insert(index, PlayAnnotation::from_str(&rest[..=end])?);
+                    rest = &rest[end + 1..];
+                } else {
+                    let end = rest.find(|c: char| c.is_whitespace() || c == '{')
+                        .unwrap_or(rest.len());
+                    record.plays.push(Play::from_str(&rest[..end])?);
+                    rest = &rest[end..];
+                }
+                rest = rest.trim_start();
+            }
+        }
+    }
+    record.starting_board = board.ok_or(BadString(String::from("Missing Board tag.")))?;
+    Ok(record)
+}
+
+/// A reader for archives containing many [`GameRecord`]s in text form, separated by blank lines.
+/// Records are read lazily, one at a time, so archives of any size can be processed without
+/// loading them into memory.
+///
+/// A record which cannot be parsed produces an error, and reading continues with the next record.
+/// An I/O error ends iteration.
+pub struct ArchiveReader<R: BufRead> {
+    lines: Lines<R>,
+    line: usize,
+    index: usize,
+    done: bool
+}
+
+impl<R: BufRead> ArchiveReader<R> {
+
+    /// Create a reader for the archive provided by the given source.
+    pub fn new(reader: R) -> Self {
+        Self { lines: reader.lines(), line: 0, index: 0, done: false }
+    }
+
+    /// Replay each record in the archive under the given rules, yielding the resulting games.
+    pub fn games<T: BoardState>(
+        self,
+        rules: Ruleset
+    ) -> impl Iterator<Item = Result<Game<T>, ArchiveError>> {
+        self.enumerate().map(move |(index, record)| {
+            record?.replay(rules).map_err(|error| ArchiveError::BadGame { index, error })
+        })
+    }
+}
+
+impl<R: BufRead> Iterator for ArchiveReader<R> {
+    type Item = Result<GameRecord, ArchiveError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None
+        }
+        let mut text = String::new();
+        let mut start = 0;
+        loop {
+            match self.lines.next() {
+                Some(Ok(l)) => {
+                    self.line += 1;
+                    if !l.trim().is_empty() {
+                        if text.is_empty() {
+                            start = self.line;
+                        }
+                        text.push_str(&l);
+                        text.push('\n');
+                    } else if !text.is_empty() {
+                        break
+                    }
+                },
+                Some(Err(e)) => {
+                    self.done = true;
+                    return Some(Err(ArchiveError::Io(e.kind())))
+                },
+                None => {
+                    self.done = true;
+                    break
+                }
+            }
+        }
+        if text.is_empty() {
+            return None
+        }
+        let index = self.index;
+        self.index += 1;
+        Some(GameRecord::from_str(&text)
+            .map_err(|error| ArchiveError::BadRecord { index, line: start, error }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::ParseError::UnsupportedVersion;
+    use crate::error::ArchiveError::{BadGame, BadRecord};
+    use crate::error::PlayInvalid::BlockedByPiece;
+    use crate::error::RecordError::{ChecksumMismatch, InvalidPlay, MissingChecksum};
+    use crate::game::clock::{SideTimeControl, TimeControl};
+    use crate::game::SmallBasicGame;
+    use crate::play::Play;
+    use crate::preset::{boards, rules};
+    use crate::record::{record_version, ArchiveReader, GameRecord, PlayAnnotation, PrintOptions, RECORD_FORMAT_VERSION};
+    use crate::board::state::SmallBasicBoardState;
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    #[test]
+    fn test_game_record() {
+        let mut game = SmallBasicGame::new(rules::BRANDUBH, boards::BRANDUBH).unwrap();
+        for p in ["d1-c1", "d3-c3", "c1-c2"] {
+            game.do_play(Play::from_str(p).unwrap()).unwrap();
+        }
+        let mut record = GameRecord::from_game(&game);
+        assert_eq!(record.starting_board, boards::BRANDUBH);
+        assert_eq!(record.verify(&rules::BRANDUBH), Err(MissingChecksum));
+        record.seal(&rules::BRANDUBH);
+        assert!(record.verify(&rules::BRANDUBH).is_ok());
+        assert!(record.verify(&rules::COPENHAGEN).is_err());
+
+        let parsed = GameRecord::from_str(&record.to_string()).unwrap();
+        assert_eq!(parsed, record);
+        let replayed = parsed.replay::<SmallBasicBoardState>(rules::BRANDUBH).unwrap();
+        assert_eq!(replayed.state, game.state);
+
+        // Truncated record
+        let mut truncated = record.clone();
+        truncated.plays.pop();
+        assert!(matches!(truncated.verify(&rules::BRANDUBH), Err(ChecksumMismatch { .. })));
+
+        let mut bad = record.clone();
+        bad.plays.push(Play::from_str("c3-c2").unwrap());
+        assert_eq!(
+            bad.replay::<SmallBasicBoardState>(rules::BRANDUBH).err(),
+            Some(InvalidPlay { index: 3, reason: BlockedByPiece })
+        );
+        assert!(GameRecord::from_str("d1-c1").is_err());
+    }
+
+    #[test]
+    fn test_annotations() {
+        let mut record = GameRecord::from_str(
+            "[Board \"7/1K5/7/7/7/7/5t1\"]\nf7-f6 {eval 0.25 clock 59.5} b2-b1 f6-f5{eval -1.5}"
+        ).unwrap();
+        assert_eq!(record.plays.len(), 3);
+        assert_eq!(record.annotations.len(), 2);
+        assert_eq!(record.annotations[&0], PlayAnnotation {
+            eval: Some(0.25),
+            clock: Some(Duration::from_millis(59_500))
+        });
+        assert_eq!(record.annotations[&2], PlayAnnotation { eval: Some(-1.5), clock: None });
+
+        record.annotations.insert(1, PlayAnnotation { eval: None, clock: Some(Duration::from_millis(1_007)) });
+        record.time_control = Some(TimeControl::asymmetric(
+            SideTimeControl { initial: Duration::from_secs(60), increment: Duration::ZERO },
+            SideTimeControl { initial: Duration::from_secs(30), increment: Duration::from_secs(1) }
+        ));
+        record.seal(&rules::BRANDUBH);
+        let text = record.to_string();
+        assert!(text.contains("[TimeControl \"60+0/30+1\"]"));
+        assert!(text.ends_with("f7-f6 {eval 0.25 clock 59.500} b2-b1 {clock 1.007} f6-f5 {eval -1.5}"));
+        assert_eq!(GameRecord::from_str(&text).unwrap(), record);
+
+        // Annotations do not affect the checksum.
+        record.annotations.clear();
+        assert!(record.verify(&rules::BRANDUBH).is_ok());
+
+        // Unknown keys are ignored.
+        assert_eq!(PlayAnnotation::from_str("{eval 1 depth 12}").unwrap().eval, Some(1.0));
+        assert!(PlayAnnotation::from_str("{eval}").is_err());
+        assert!(GameRecord::from_str("[Board \"7/7/7/3K3/7/7/7\"]\n{eval 1} d4-d5").is_err());
+        assert!(GameRecord::from_str("[Board \"7/7/7/3K3/7/7/7\"]\nd4-d5 {eval 1").is_err());
+    }
+
+    #[test]
+    fn test_record_versions() {
+        let record = GameRecord {
+            starting_board: String::from(boards::BRANDUBH),
+            plays: vec![Play::from_str("d1-c1").unwrap()],
+            ..Default::default()
+        };
+        let text = record.to_string();
+        assert!(text.starts_with("[Version \"2\"]\n"));
+        assert_eq!(record_version(&text), Ok(RECORD_FORMAT_VERSION));
+
+        // Version 1 records had no version tag.
+        let v1 = format!("[Board \"{}\"]\n[Swapped \"true\"]\nd1-c1", boards::BRANDUBH);
+        assert_eq!(record_version(&v1), Ok(1));
+        let migrated = GameRecord::from_str(&v1).unwrap();
+        assert!(migrated.sides_swapped);
+        assert_eq!(migrated.plays, record.plays);
+
+        let future = text.replace("[Version \"2\"]", "[Version \"99\"]");
+        assert_eq!(GameRecord::from_str(&future), Err(UnsupportedVersion(99)));
+        assert!(GameRecord::from_str(&text.replace("[Version \"2\"]", "[Version \"0\"]")).is_err());
+    }
+
+    #[test]
+    fn test_archive_reader() {
+        let archive = format!(
+            "\n[Board \"{0}\"]\nd1-c1 d3-c3\n\n\n[Board \"{0}\"]\nd1-x9\n\n[Board \"{0}\"]\nd1-c1\n\
+            [Board \"{0}\"]\nd1-c1 d1-c1\n\n[Board \"{0}\"]\n\n",
+            boards::BRANDUBH
+        );
+        let records: Vec<_> = ArchiveReader::new(archive.as_bytes()).collect();
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0].as_ref().unwrap().plays.len(), 2);
+        assert!(matches!(records[1], Err(BadRecord { index: 1, line: 6, .. })));
+        // A record without a separating blank line is merged with the one before, giving a second
+        // Board tag which replaces the first.
+        assert_eq!(records[2].as_ref().unwrap().plays.len(), 3);
+        assert!(records[3].as_ref().unwrap().plays.is_empty());
+
+        let games: Vec<_> = ArchiveReader::new(archive.as_bytes())
+            .games::<SmallBasicBoardState>(rules::BRANDUBH)
+            .collect();
+        assert_eq!(games.len(), 4);
+        assert_eq!(games[0].as_ref().unwrap().play_history.len(), 2);
+        assert!(matches!(games[1], Err(BadRecord { index: 1, .. })));
+        assert!(matches!(games[2], Err(BadGame { index: 2, .. })));
+        assert_eq!(games[3].as_ref().unwrap().play_history.len(), 0);
+    }
+
+    #[test]
+    fn test_print_record() {
+        let record = GameRecord {
+            starting_board: String::from("7/1K5/7/7/7/7/5t1"),
+            plays: ["f7-f6", "b2-b1", "f6-f5", "b1-a1"].iter().map(|p| Play::from_str(p).unwrap()).collect(),
+            ..Default::default()
+        };
+        let printed = record.print::<SmallBasicBoardState>(rules::BRANDUBH, &PrintOptions {
+            every: Some(2),
+            ..Default::default()
+        }).unwrap();
+        let lines: Vec<&str> = printed.lines().collect();
+        assert_eq!(lines[0], "Board: 7/1K5/7/7/7/7/5t1");
+        assert_eq!(lines[1], "1. Attacker f7-f6");
+        assert_eq!(lines[2], "2. Defender b2-b1");
+        assert_eq!(lines[3], "    .K.....");
+        assert_eq!(lines[10], "3. Attacker f6-f5");
+        assert_eq!(lines[12], "    K......");
+        assert_eq!(*lines.last().unwrap(), "Result: Defender won (KingEscaped)");
+        assert_eq!(lines.len(), 20);
+    }
+}
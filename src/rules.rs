@@ -1,8 +1,9 @@
-use crate::pieces::{PieceSet, Side};
+use crate::pieces::{Piece, PieceSet, PieceType, Side};
+use crate::tiles::Tile;
 use std::cmp::PartialEq;
 
 /// Rules relating to who may occupy/pass through the throne.
-#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
 pub enum ThroneRule {
     /// Board has no throne
     NoThrone,
@@ -16,9 +17,18 @@ pub enum ThroneRule {
     KingEntry
 }
 
+/// A tile which the defender must bring the king to in order to win.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
+pub enum KingTarget {
+    /// The king must reach the throne.
+    Throne,
+    /// The king must reach the given tile.
+    Tile(Tile)
+}
+
 /// Rules relating to whether and when the king is strong (must be surrounded by hostile tiles on
 /// all four sides to be captured).
-#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
 pub enum KingStrength {
     /// King must be surrounded by four hostile pieces or tiles to be captured.
     Strong,
@@ -30,7 +40,7 @@ pub enum KingStrength {
 }
 
 /// Whether king may participate in captures.
-#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
 pub enum KingAttack {
     /// King can participate in captures in same way as normal pieces.
     Armed,
@@ -41,17 +51,105 @@ pub enum KingAttack {
     Hammer
 }
 
+/// How guards may be captured.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
+pub enum GuardCapture {
+    /// Guards are captured in the same way as soldiers.
+    Normal,
+    /// Guards may only be captured against a hostile tile or edge, not by being flanked by two
+    /// enemy pieces.
+    HostileTileOnly,
+    /// Guards must be surrounded on all four sides by hostile pieces or tiles to be captured.
+    FourSided
+}
 
 /// A struct describing what pieces certain special tiles are considered hostile to.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct HostilityRules {
     pub(crate) throne: PieceSet,
     pub(crate) corners: PieceSet,
     pub(crate) edge: PieceSet
 }
 
+/// A limit on the number of tiles certain pieces may move in a single play.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RangeLimit {
+    /// The pieces to which the limit applies.
+    pub pieces: PieceSet,
+    /// The maximum number of tiles those pieces may move.
+    pub max_distance: u8
+}
+
+/// A rule under which one side makes two plays in a row at a certain point in the game, usually as
+/// a balancing measure.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
+pub struct DoublePlayRule {
+    /// The side that makes two plays in a row.
+    pub side: Side,
+    /// The turn of that side (counting from zero, so `0` is the side's first turn) at which it
+    /// makes two plays.
+    pub turn: usize
+}
+
+/// Rules ending the game once a certain number of plays have been made, ensuring that games
+/// terminate.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
+pub struct MoveLimitRules {
+    /// If set, the game ends once this many plays have been made in total (by both sides).
+    pub total_plays: Option<usize>,
+    /// If set, the game ends once this many plays have been made (by both sides) without a capture.
+    pub plays_without_capture: Option<usize>,
+    /// If `true`, when the limit is reached the game is won by the side that is ahead on material
+    /// (see [`PieceValues`]), and only drawn if material is equal. If `false`, the game is drawn.
+    pub adjudicate_on_material: bool
+}
+
+/// A rule under which certain pieces are promoted to a different type of piece when they reach
+/// certain tiles.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PromotionRule {
+    /// The pieces which are promoted.
+    pub pieces: PieceSet,
+    /// The tiles on which those pieces are promoted.
+    pub tiles: &'static [Tile],
+    /// The type of piece to which they are promoted.
+    pub promote_to: PieceType
+}
+
+/// Restrictions that apply only to the first play of the game.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FirstPlayRestrictions {
+    /// Tiles onto which the first play may not move a piece (pieces may still pass through them).
+    pub forbidden_tiles: &'static [Tile],
+    /// Whether the first play may capture any pieces.
+    pub may_capture: bool
+}
+
+/// A class of special tiles (such as camps, bases or sanctuaries) other than the throne and
+/// corners, and the rules that apply to them. The positions of the tiles are given explicitly, so
+/// a set of special tile rules is specific to a particular board size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpecialTileRules {
+    /// A descriptive name for this class of tile (eg, "camp").
+    pub name: &'static str,
+    /// The tiles belonging to this class.
+    pub tiles: &'static [Tile],
+    /// The pieces to which tiles of this class are hostile (when unoccupied).
+    pub hostile_to: PieceSet,
+    /// The pieces that may end a move on a tile of this class.
+    pub may_enter: PieceSet,
+    /// The pieces that may pass through a tile of this class without stopping.
+    pub may_pass: PieceSet,
+    /// Whether a piece that starts its move on one of these tiles may move onto or through the
+    /// other tiles of this class, regardless of [`Self::may_enter`] and [`Self::may_pass`]. This
+    /// can be used to implement camps, where attackers may move about within their starting camp
+    /// but may not re-enter it once they have left. In that case, each camp should be specified as
+    /// a separate class.
+    pub residents_exempt: bool
+}
+
 /// Rules relating to shieldwall captures.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct ShieldwallRules {
     /// Whether a shieldwall may be closed at one end by a corner.
     pub corners_may_close: bool,
@@ -60,7 +158,7 @@ pub struct ShieldwallRules {
 }
 
 /// Circumstances in which attacker wins as a result of enclosing all defenders.
-#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Hash)]
 pub enum EnclosureWinRules {
     /// Attacker wins if defender is entirely surrounded, even if defender has edge access. 
     WithEdgeAccess,
@@ -69,7 +167,7 @@ pub enum EnclosureWinRules {
 }
 
 /// Consequence of repeated plays.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct RepetitionRule {
     /// Number of repetitions that will trigger the rule. 
     pub(crate) n_repetitions: usize,
@@ -78,12 +176,80 @@ pub struct RepetitionRule {
     pub(crate) is_loss: bool
 }
 
+/// Values assigned to each type of piece, used to assess the material balance of a position.
+///
+/// Because the attacker usually starts with twice as many pieces as the defender, the value of a
+/// piece is multiplied by a weight depending on its side. With the [`Self::standard`] values, the
+/// starting position of the common variants is materially even.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PieceValues {
+    /// Value of a king. Usually zero, as the king is not captured in the ordinary way and its
+    /// capture ends the game anyway.
+    pub king: i32,
+    /// Value of a soldier.
+    pub soldier: i32,
+    /// Value of a knight.
+    pub knight: i32,
+    /// Value of a commander.
+    pub commander: i32,
+    /// Value of a guard.
+    pub guard: i32,
+    /// Value of a mercenary.
+    pub mercenary: i32,
+    /// Multiplier applied to the value of each attacking piece.
+    pub attacker_weight: i32,
+    /// Multiplier applied to the value of each defending piece.
+    pub defender_weight: i32
+}
+
+impl PieceValues {
+
+    /// Reasonable default values for a game with a 2:1 ratio of attackers to defenders.
+    pub const fn standard() -> Self {
+        Self {
+            king: 0,
+            soldier: 1,
+            knight: 2,
+            commander: 2,
+            guard: 2,
+            mercenary: 1,
+            attacker_weight: 1,
+            defender_weight: 2
+        }
+    }
+
+    /// Get the (weighted) value of the given piece.
+    pub fn value_of(&self, piece: Piece) -> i32 {
+        let base = match piece.piece_type {
+            PieceType::King => self.king,
+            PieceType::Soldier => self.soldier,
+            PieceType::Knight => self.knight,
+            PieceType::Commander => self.commander,
+            PieceType::Guard => self.guard,
+            PieceType::Mercenary => self.mercenary
+        };
+        base * match piece.side {
+            Side::Attacker => self.attacker_weight,
+            Side::Defender => self.defender_weight
+        }
+    }
+}
+
+impl Default for PieceValues {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
 /// A set of rules for a tafl game.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Ruleset {
     /// Whether defender wins by getting king to edge of board (otherwise, corner escape is
     /// assumed).
     pub edge_escape: bool,
+    /// If set, the defender wins by moving the king onto the given tile, rather than by escaping to
+    /// the edge or corner.
+    pub king_target: Option<KingTarget>,
     /// Whether the king is strong (must be surrounded by four opponents or hostile tiles to be
     /// captured).
     pub king_strength: KingStrength,
@@ -99,14 +265,40 @@ pub struct Ruleset {
     pub may_enter_corners: PieceSet,
     /// What special tiles are hostile to what pieces.
     pub hostility: HostilityRules,
+    /// Classes of special tile other than the throne and corners, and the rules that apply to
+    /// them.
+    pub special_tiles: &'static [SpecialTileRules],
     /// Types of piece whose movement is restricted to one tile per move.
     pub slow_pieces: PieceSet,
+    /// Limits on the distance that certain pieces may move in a single play. If more than one limit
+    /// applies to a piece, the lowest applies.
+    pub range_limits: &'static [RangeLimit],
+    /// Rules under which pieces are promoted to a different type of piece on reaching certain
+    /// tiles. Only the first applicable rule is applied.
+    pub promotions: &'static [PromotionRule],
+    /// Whether the "pie rule" applies, ie, whether after the first play of the game the second
+    /// player may choose to swap sides instead of making a play.
+    pub swap_rule: bool,
+    /// A rule allowing one side to make two plays in a row at some point, if any.
+    pub double_play: Option<DoublePlayRule>,
+    /// Restrictions on the first play of the game, if any.
+    pub first_play: Option<FirstPlayRestrictions>,
     /// Which side goes first.
     pub starting_side: Side,
     /// Whether attacker can win by enclosing all defending pieces.
     pub enclosure_win: Option<EnclosureWinRules>,
+    /// Whether the attacker wins by moving one of its pieces onto the empty throne. This only has
+    /// an effect if the rules on throne movement permit attackers to enter the throne.
+    pub throne_occupation_win: bool,
+    /// Whether the attacker wins as soon as it has captured every defending piece other than the
+    /// king. (Either side always wins if it captures every one of the other side's pieces.)
+    pub annihilation_win: bool,
+    /// If set, a side wins as soon as it has captured the given number of enemy pieces.
+    pub capture_win: Option<u16>,
     /// Whether repeated moves result in a loss or draw.
     pub repetition_rule: Option<RepetitionRule>,
+    /// Rules ending the game after a certain number of plays, if any.
+    pub move_limit: Option<MoveLimitRules>,
     /// Whether the game is drawn when one player has no legal plays available to it. If `false`,
     /// the player with no available plays loses.
     pub draw_on_no_plays: bool,
@@ -114,5 +306,13 @@ pub struct Ruleset {
     /// three enemies and one friendly soldier, that friendly soldier may be captured against the
     /// occupied throne).
     pub linnaean_capture: bool,
+    /// Whether a commander may capture an enemy piece on its own against the edge of the board or
+    /// an empty special tile (throne, corner or other special tile), even if the edge or tile is
+    /// not otherwise hostile to that piece.
+    pub commander_lone_capture: bool,
+    /// How guards may be captured.
+    pub guard_capture: GuardCapture,
+    /// The values of each piece, used to assess material balance.
+    pub piece_values: PieceValues,
 }
 
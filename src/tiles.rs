@@ -2,12 +2,12 @@ use crate::error::ParseError::{BadChar, EmptyString};
 use crate::error::ParseError;
 use crate::tiles::Axis::{Horizontal, Vertical};
 use std::fmt::{Debug, Display, Formatter};
-use std::ops::Add;
+use std::ops::{Add, BitAnd, BitOr, Not, Sub};
 use std::str::FromStr;
 
 /// An offset which can be applied to [`Coords`] and which is composed of the axis of movement and
 /// an offset along that axis.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
 pub struct AxisOffset {
     /// The axis along which the tile is offset.
     pub axis: Axis,
@@ -29,7 +29,7 @@ impl AxisOffset {
 
 /// An offset which can be applied to [`Coords`] and which is composed of the row and column offset
 /// to be applied.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct RowColOffset{
     row: i8,
     col: i8
@@ -49,7 +49,7 @@ impl RowColOffset {
 /// An unbounded row-column pair representing a hypothetical location, which may or may not be on
 /// the board. Can be used to represent out-of-bounds locations, including those with negative row
 /// or column values.
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
 pub struct Coords {
     pub row: i8,
     pub col: i8
@@ -113,7 +113,7 @@ pub struct Tile {
 impl Tile {
     
     /// Create a new [`Tile`] with the given row and column.
-    pub fn new(row: u8, col: u8) -> Self {
+    pub const fn new(row: u8, col: u8) -> Self {
         Self { row, col }
     }
     
@@ -163,7 +163,7 @@ impl From<Tile> for (u8, u8) {
 }
 
 /// A single axis of movement (vertical or horizontal).
-#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash)]
+#[derive(Eq, PartialEq, Debug, Clone, Copy, Hash, PartialOrd, Ord)]
 pub enum Axis {
     Vertical = 0,
     Horizontal = 0x80
@@ -212,6 +212,192 @@ impl Iterator for TileIterator {
     }
 }
 
+/// A set of tiles, stored as a bitset, on a board of size up to 21x21 ([`TileSet::MAX_BOARD_LEN`]).
+/// This is cheap to copy and to combine with other sets using the `|` (union), `&`
+/// (intersection) and `-` (difference) operators. Iteration is in order of row, then column.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct TileSet {
+    bits: [u64; TILE_SET_WORDS]
+}
+
+/// The number of words needed to store a bit for every tile on the largest supported board.
+const TILE_SET_WORDS: usize = (21 * 21usize).div_ceil(64);
+
+impl TileSet {
+
+    /// The side length of the largest board whose tiles can be stored in a [`TileSet`].
+    pub const MAX_BOARD_LEN: u8 = 21;
+
+    /// Create an empty set.
+    pub const fn new() -> Self {
+        Self { bits: [0; TILE_SET_WORDS] }
+    }
+
+    /// Create a set of every tile on a board with the given side length.
+    pub fn full(side_len: u8) -> Self {
+        TileIterator::new(side_len).collect()
+    }
+
+    /// The index of the bit representing the given tile. Panics if the tile is out of range.
+    fn index(tile: Tile) -> (usize, u64) {
+        assert!(
+            tile.row < Self::MAX_BOARD_LEN && tile.col < Self::MAX_BOARD_LEN,
+            "Tile out of range for TileSet."
+        );
+        let i = tile.row as usize * Self::MAX_BOARD_LEN as usize + tile.col as usize;
+        (i / 64, 1 << (i % 64))
+    }
+
+    /// Add the given tile to the set. Returns whether the tile was newly added.
+    pub fn insert(&mut self, tile: Tile) -> bool {
+        let (word, mask) = Self::index(tile);
+        let added = self.bits[word] & mask == 0;
+        self.bits[word] |= mask;
+        added
+    }
+
+    /// Remove the given tile from the set. Returns whether the tile was present.
+    pub fn remove(&mut self, tile: Tile) -> bool {
+        let (word, mask) = Self::index(tile);
+        let present = self.bits[word] & mask != 0;
+        self.bits[word] &= !mask;
+        present
+    }
+
+    /// Whether the set contains the given tile.
+    pub fn contains(&self, tile: Tile) -> bool {
+        if tile.row >= Self::MAX_BOARD_LEN || tile.col >= Self::MAX_BOARD_LEN {
+            return false
+        }
+        let (word, mask) = Self::index(tile);
+        self.bits[word] & mask != 0
+    }
+
+    /// The number of tiles in the set.
+    pub fn len(&self) -> usize {
+        self.bits.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Whether the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|w| *w == 0)
+    }
+
+    /// Iterate over the tiles in the set, in order of row, then column.
+    pub fn iter(&self) -> TileSetIter {
+        TileSetIter { bits: self.bits, word: 0 }
+    }
+}
+
+impl Debug for TileSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter().map(|t| t.to_string())).finish()
+    }
+}
+
+impl BitOr for TileSet {
+    type Output = Self;
+
+    fn bitor(mut self, rhs: Self) -> Self {
+        for (a, b) in self.bits.iter_mut().zip(rhs.bits) {
+            *a |= b;
+        }
+        self
+    }
+}
+
+impl BitAnd for TileSet {
+    type Output = Self;
+
+    fn bitand(mut self, rhs: Self) -> Self {
+        for (a, b) in self.bits.iter_mut().zip(rhs.bits) {
+            *a &= b;
+        }
+        self
+    }
+}
+
+impl Sub for TileSet {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self & !rhs
+    }
+}
+
+/// The complement of the set, within the 21x21 range of the set. To get the complement within a
+/// smaller board, intersect the result with [`TileSet::full`].
+impl Not for TileSet {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        let mut complement = Self { bits: self.bits.map(|w| !w) };
+        // Clear the bits beyond the last tile.
+        let n_tiles = Self::MAX_BOARD_LEN as usize * Self::MAX_BOARD_LEN as usize;
+        complement.bits[TILE_SET_WORDS - 1] &= (1 << (n_tiles - (TILE_SET_WORDS - 1) * 64)) - 1;
+        complement
+    }
+}
+
+impl FromIterator<Tile> for TileSet {
+    fn from_iter<I: IntoIterator<Item = Tile>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<Tile> for TileSet {
+    fn extend<I: IntoIterator<Item = Tile>>(&mut self, iter: I) {
+        for tile in iter {
+            self.insert(tile);
+        }
+    }
+}
+
+impl IntoIterator for TileSet {
+    type Item = Tile;
+    type IntoIter = TileSetIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for &TileSet {
+    type Item = Tile;
+    type IntoIter = TileSetIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the tiles in a [`TileSet`].
+pub struct TileSetIter {
+    bits: [u64; TILE_SET_WORDS],
+    word: usize
+}
+
+impl Iterator for TileSetIter {
+    type Item = Tile;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.word < self.bits.len() {
+            let w = self.bits[self.word];
+            if w == 0 {
+                self.word += 1;
+                continue
+            }
+            let bit = w.trailing_zeros() as usize;
+            self.bits[self.word] &= w - 1;
+            let i = self.word * 64 + bit;
+            let len = TileSet::MAX_BOARD_LEN as usize;
+            return Some(Tile::new((i / len) as u8, (i % len) as u8))
+        }
+        None
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -219,7 +405,7 @@ mod tests {
     use crate::error::PlayError;
     use crate::play::Play;
     use crate::tiles::Axis::{Horizontal, Vertical};
-    use crate::tiles::Tile;
+    use crate::tiles::{Tile, TileSet};
     use std::str::FromStr;
 
     #[test]
@@ -323,4 +509,33 @@ mod tests {
         let parsed_m = Play::from_str("!5-d5");
         assert_eq!(parsed_m, Err(BadChar('!')));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_tile_set() {
+        let mut set = TileSet::new();
+        assert!(set.is_empty());
+        assert!(set.insert(Tile::new(3, 4)));
+        assert!(!set.insert(Tile::new(3, 4)));
+        assert!(set.insert(Tile::new(18, 18)));
+        assert!(set.insert(Tile::new(0, 0)));
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(Tile::new(3, 4)));
+        assert!(!set.contains(Tile::new(4, 3)));
+        assert!(!set.contains(Tile::new(21, 0)));
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            vec![Tile::new(0, 0), Tile::new(3, 4), Tile::new(18, 18)]
+        );
+        assert!(set.remove(Tile::new(0, 0)));
+        assert!(!set.remove(Tile::new(0, 0)));
+
+        let full = TileSet::full(7);
+        assert_eq!(full.len(), 49);
+        assert_eq!((set & full).iter().collect::<Vec<_>>(), vec![Tile::new(3, 4)]);
+        assert_eq!((set | full).len(), 50);
+        assert_eq!((full - set).len(), 48);
+        assert_eq!((!TileSet::new()).len(), 441);
+        assert_eq!((!full & TileSet::full(8)).len(), 15);
+        assert_eq!(format!("{set:?}"), "{\"e4\", \"s19\"}");
+    }
+}
@@ -1,4 +1,5 @@
-use std::hash::Hash;
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
 #[cfg(test)]
 use crate::tiles::Tile;
 
@@ -24,25 +25,27 @@ impl<T: Hash + Eq + Copy> UniqueStack<T> {
     }
 }
 
-/// A queue of a fixed size. Pushing a new value to the end of the queue drops the first item in the
-/// queue.
+/// A queue of a fixed size `N`, implemented as a ring buffer. The queue is always full: pushing a
+/// new value to the end of the queue drops the first (oldest) item in the queue. This makes it
+/// suitable for keeping a record of the most recent `N` items of something, such as recent plays,
+/// or killer moves in a search engine.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
-pub(crate) struct FixedSizeQueue<T, const N: usize> {
+pub struct FixedSizeQueue<T, const N: usize> {
     queue: [T; N],
     first_i: usize
 }
 
 impl<T, const N: usize> FixedSizeQueue<T, N> {
 
-    #[cfg(test)]
-    pub(crate) fn new(queue: [T; N]) -> Self {
+    /// Create a new queue containing the given items, with the first item in the array being the
+    /// first (oldest) item in the queue.
+    pub fn new(queue: [T; N]) -> Self {
         Self {
             queue,
             first_i: 0
         }
     }
 
-    #[cfg(test)]
     fn last_i(&self) -> usize {
         if self.first_i == 0 {
             N - 1
@@ -51,12 +54,13 @@ impl<T, const N: usize> FixedSizeQueue<T, N> {
         }
     }
 
-    #[cfg(test)]
-    pub(crate) fn last(&self) -> &T {
+    /// The last (most recently pushed) item in the queue.
+    pub fn last(&self) -> &T {
         &self.queue[self.last_i()]
     }
 
-    pub(crate) fn push(&mut self, value: T) {
+    /// Push a value to the end of the queue, dropping the first item.
+    pub fn push(&mut self, value: T) {
         self.queue[self.first_i] = value;
         self.first_i = if self.first_i == N - 1 {
             0
@@ -65,10 +69,32 @@ impl<T, const N: usize> FixedSizeQueue<T, N> {
         }
     }
 
-    pub(crate) fn first(&self) -> &T {
+    /// The first (oldest) item in the queue.
+    pub fn first(&self) -> &T {
         &self.queue[self.first_i]
     }
 
+    /// Get the item at the given index, where index 0 is the first (oldest) item. Returns `None`
+    /// if the index is out of bounds.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < N {
+            Some(&self.queue[(self.first_i + index) % N])
+        } else {
+            None
+        }
+    }
+
+    /// The number of items the queue holds (which is always `N`).
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Iterate over the items in the queue, from first (oldest) to last (newest).
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item=&T> + '_ {
+        let (newer, older) = self.queue.split_at(self.first_i);
+        older.iter().chain(newer.iter())
+    }
+
 }
 
 impl<T: Default + Copy, const N: usize> Default for FixedSizeQueue<T, N> {
@@ -80,6 +106,250 @@ impl<T: Default + Copy, const N: usize> Default for FixedSizeQueue<T, N> {
     }
 }
 
+/// A vector which stores its first `N` items inline, and only moves further items to the heap.
+/// This avoids an allocation where the number of items is usually small, such as the pieces
+/// captured by a single play, without limiting how many items can be stored.
+#[derive(Clone)]
+pub struct InlineVec<T: Copy, const N: usize> {
+    items: [Option<T>; N],
+    len: usize,
+    spill: Vec<T>
+}
+
+impl<T: Copy, const N: usize> InlineVec<T, N> {
+
+    /// Create a new, empty vector.
+    pub fn new() -> Self {
+        Self { items: [None; N], len: 0, spill: Vec::new() }
+    }
+
+    /// The number of items in the vector.
+    pub fn len(&self) -> usize {
+        self.len + self.spill.len()
+    }
+
+    /// Whether the vector is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of items the vector can hold without allocating (which is always `N`).
+    pub const fn inline_capacity(&self) -> usize {
+        N
+    }
+
+    /// Whether the items have outgrown the inline storage, so that some are stored on the heap.
+    pub fn spilled(&self) -> bool {
+        !self.spill.is_empty()
+    }
+
+    /// Push a value to the end of the vector.
+    pub fn push(&mut self, value: T) {
+        if self.len < N {
+            self.items[self.len] = Some(value);
+            self.len += 1;
+        } else {
+            self.spill.push(value);
+        }
+    }
+
+    /// Get the item at the given index, or `None` if the index is out of bounds.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len {
+            self.items[index].as_ref()
+        } else {
+            self.spill.get(index - self.len)
+        }
+    }
+
+    /// Iterate over the items in the vector, in the order they were pushed.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item=&T> + '_ {
+        self.items[..self.len].iter().flatten().chain(self.spill.iter())
+    }
+}
+
+impl<T: Copy + PartialEq, const N: usize> InlineVec<T, N> {
+
+    /// Whether the vector contains the given value.
+    pub fn contains(&self, value: &T) -> bool {
+        self.iter().any(|v| v == value)
+    }
+
+    /// Push a value to the end of the vector, unless it is already present. Returns whether the
+    /// value was pushed.
+    pub fn insert(&mut self, value: T) -> bool {
+        if self.contains(&value) {
+            false
+        } else {
+            self.push(value);
+            true
+        }
+    }
+}
+
+impl<T: Copy, const N: usize> Default for InlineVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Copy + Debug, const N: usize> Debug for InlineVec<T, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Copy + PartialEq, const N: usize> PartialEq for InlineVec<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Copy + Eq, const N: usize> Eq for InlineVec<T, N> {}
+
+impl<T: Copy + Hash, const N: usize> Hash for InlineVec<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        for item in self {
+            item.hash(state);
+        }
+    }
+}
+
+impl<T: Copy, const N: usize> FromIterator<T> for InlineVec<T, N> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut v = Self::new();
+        v.extend(iter);
+        v
+    }
+}
+
+impl<T: Copy, const N: usize> Extend<T> for InlineVec<T, N> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<'a, T: Copy, const N: usize> IntoIterator for &'a InlineVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = std::iter::Chain<
+        std::iter::Flatten<std::slice::Iter<'a, Option<T>>>,
+        std::slice::Iter<'a, T>
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items[..self.len].iter().flatten().chain(self.spill.iter())
+    }
+}
+
+impl<T: Copy, const N: usize, const M: usize> From<[T; M]> for InlineVec<T, N> {
+    fn from(values: [T; M]) -> Self {
+        values.into_iter().collect()
+    }
+}
+
+/// A source of pseudo-random numbers. All randomised functionality in this crate takes a
+/// caller-provided `Rng` rather than using a global random number generator, so that results can
+/// be reproduced (for example, in tests or simulations) by using a seeded generator.
+///
+/// Only [`Self::next_u64`] needs to be implemented, so it is straightforward to wrap the
+/// generator of another crate such as `rand`.
+pub trait Rng {
+
+    /// Return the next pseudo-random 64-bit number.
+    fn next_u64(&mut self) -> u64;
+
+    /// Return a pseudo-random number in the range `0..n`. Panics if `n` is zero.
+    fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "Range must not be empty.");
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Choose a pseudo-random item from the given slice, or return `None` if it is empty.
+    fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            None
+        } else {
+            Some(&items[self.below(items.len())])
+        }
+    }
+
+    /// Shuffle the given slice in place.
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+impl<R: Rng + ?Sized> Rng for &mut R {
+    fn next_u64(&mut self) -> u64 {
+        (**self).next_u64()
+    }
+}
+
+/// A minimal, fast [SplitMix64](https://prng.di.unimi.it/splitmix64.c) pseudo-random number
+/// generator. It is not cryptographically secure, but the same seed always produces the same
+/// sequence of numbers on any platform.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SplitMix64(u64);
+
+impl SplitMix64 {
+
+    /// Create a new generator from the given seed.
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+}
+
+impl Rng for SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+}
+
+/// A 64-bit [FNV-1a](http://www.isthe.com/chongo/tech/comp/fnv/) hasher. Unlike the standard
+/// library's default hasher it is not randomly keyed, so the same input always produces the same
+/// hash. Note that the standard library's [`Hash`] implementations for integers write bytes in
+/// native byte order, so to get the same hash on every platform, write byte slices directly
+/// (using [`Hasher::write`]) rather than hashing integers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= *b as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+/// Emits a `tracing` event at debug level with the given arguments, if the `tracing` feature is
+/// enabled. Otherwise does nothing (and the arguments are not evaluated).
+macro_rules! debug_event {
+    ($( $arg: tt )*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($( $arg )*);
+    };
+}
+
 #[cfg(test)]
 /// Creates a [`std::collections::HashSet`] containing the arguments, similar to [`vec!`].
 macro_rules! hashset {
@@ -109,7 +379,7 @@ pub(crate) fn check_tile_vec(actual: Vec<Tile>, expected: Vec<Tile>) {
 
 #[cfg(test)]
 mod tests {
-    use crate::utils::FixedSizeQueue;
+    use crate::utils::{FixedSizeQueue, InlineVec, Rng, SplitMix64};
 
     #[test]
     fn test_fixed_queue() {
@@ -122,5 +392,55 @@ mod tests {
         deque.push(50);
         assert_eq!(*deque.first(), 3);
         assert_eq!(*deque.last(), 50);
+        assert_eq!(deque.capacity(), 5);
+        assert_eq!(deque.iter().copied().collect::<Vec<_>>(), vec![3, 4, 5, 99, 50]);
+        assert_eq!(deque.iter().next_back(), Some(&50));
+        assert_eq!(deque.get(1), Some(&4));
+        assert_eq!(deque.get(5), None);
+    }
+
+    #[test]
+    fn test_inline_vec() {
+        let mut v: InlineVec<u8, 3> = InlineVec::new();
+        assert!(v.is_empty());
+        v.push(1);
+        assert!(v.insert(2));
+        assert!(!v.insert(1));
+        assert_eq!(v.len(), 2);
+        assert!(v.contains(&2));
+        assert_eq!(v.get(1), Some(&2));
+        assert_eq!(v.get(2), None);
+        assert_eq!(v, [1, 2].into());
+        assert_ne!(v, [2, 1].into());
+        v.push(3);
+        assert_eq!(v.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(format!("{v:?}"), "[1, 2, 3]");
+        assert!(!v.spilled());
+
+        // Items beyond the inline capacity are moved to the heap, transparently to the caller.
+        v.push(4);
+        assert!(v.spilled());
+        assert_eq!(v.len(), 4);
+        assert_eq!(v.get(3), Some(&4));
+        assert_eq!(v.iter().rev().copied().collect::<Vec<_>>(), vec![4, 3, 2, 1]);
+        assert_eq!(v, [1, 2, 3, 4].into());
+        assert_ne!(v, [1, 2, 3].into());
+        assert_eq!(format!("{v:?}"), "[1, 2, 3, 4]");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_rng() {
+        let mut rng = SplitMix64::new(0);
+        let first = rng.next_u64();
+        assert_eq!(SplitMix64::new(0).next_u64(), first);
+        assert_ne!(SplitMix64::new(1).next_u64(), first);
+        for n in 1..20 {
+            assert!(rng.below(n) < n);
+        }
+        let mut items: Vec<u8> = (0..10).collect();
+        rng.shuffle(&mut items);
+        items.sort();
+        assert_eq!(items, (0..10).collect::<Vec<_>>());
+        assert!(rng.choose::<u8>(&[]).is_none());
+    }
+}